@@ -2,18 +2,31 @@ use crate::commands::clauses::limit::LimitClause;
 use crate::commands::clauses::order::OrderByClause;
 use crate::commands::clauses::r#where::WhereClause;
 use crate::database::slice::TableSlice;
+use crate::database::value::IntermediateValue;
 use crate::errors::Error;
 
 pub trait Clause {
     fn apply(&self, slice: TableSlice) -> Result<TableSlice, Error>;
+    fn stage(&self) -> &'static str;
+    fn detail(&self) -> String;
 }
 
+#[derive(Clone, Debug)]
 pub enum AnyClause {
     Where(WhereClause),
     Order(OrderByClause),
     Limit(LimitClause),
 }
 
+impl AnyClause {
+    pub fn values_mut(&mut self) -> Vec<&mut IntermediateValue> {
+        match self {
+            Self::Where(clause) => clause.evaluation.values_mut(),
+            Self::Order(_) | Self::Limit(_) => Vec::new(),
+        }
+    }
+}
+
 impl Clause for AnyClause {
     fn apply(&self, slice: TableSlice) -> Result<TableSlice, Error> {
         match self {
@@ -22,4 +35,20 @@ impl Clause for AnyClause {
             Self::Limit(clause) => clause.apply(slice),
         }
     }
+
+    fn stage(&self) -> &'static str {
+        match self {
+            Self::Where(clause) => clause.stage(),
+            Self::Order(clause) => clause.stage(),
+            Self::Limit(clause) => clause.stage(),
+        }
+    }
+
+    fn detail(&self) -> String {
+        match self {
+            Self::Where(clause) => clause.detail(),
+            Self::Order(clause) => clause.detail(),
+            Self::Limit(clause) => clause.detail(),
+        }
+    }
 }
@@ -1,30 +1,117 @@
 ﻿use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+use crate::database::json::Json;
 use crate::database::record::Record;
-use crate::database::value::{compare_value_intermediate_value, IntermediateValue};
+use crate::database::temporal::DatePart;
+use crate::database::value::{compare_value_intermediate_value, escape_string, IntermediateValue, Value};
 use crate::errors::Error;
+use crate::database::ident::{format_json_path, quote_ident};
 
+#[derive(Clone, Debug)]
 pub enum CompOp {
     Eq,
     Neq,
     Leq,
     Lt,
     Geq,
-    Gt
+    Gt,
+    Contains,
+    Is,
+    IsNot,
+}
+
+impl Display for CompOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let op = match self {
+            CompOp::Eq => "=",
+            CompOp::Neq => "!=",
+            CompOp::Leq => "<=",
+            CompOp::Lt => "<",
+            CompOp::Geq => ">=",
+            CompOp::Gt => ">",
+            CompOp::Contains => "CONTAINS",
+            CompOp::Is => "IS",
+            CompOp::IsNot => "IS NOT",
+        };
+
+        write!(f, "{}", op)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum FieldFunction {
+    Extract(DatePart),
+    Length,
+    Path(Vec<String>),
+}
+
+impl FieldFunction {
+    pub fn apply(&self, value: &Value) -> Result<Value, Error> {
+        match (self, value) {
+            (FieldFunction::Extract(part), value) => Ok(Value::Int(part.extract(value)?)),
+            (FieldFunction::Length, Value::String(s)) => Ok(Value::Int(s.chars().count() as i64)),
+            (FieldFunction::Length, Value::Bytes(b)) => Ok(Value::Int(b.len() as i64)),
+            (FieldFunction::Length, Value::List(items)) => Ok(Value::Int(items.len() as i64)),
+            (FieldFunction::Length, Value::Json(Json::Array(items))) => Ok(Value::Int(items.len() as i64)),
+            (FieldFunction::Length, Value::Json(Json::Object(members))) => Ok(Value::Int(members.len() as i64)),
+            (FieldFunction::Length, other) => Err(Error::TypeError(format!("Cannot take the length of {:?}", other))),
+            (FieldFunction::Path(keys), value) => value.json_path(keys),
+        }
+    }
+}
+
+impl Display for FieldFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldFunction::Extract(part) => write!(f, "{}", part),
+            FieldFunction::Length => write!(f, "LENGTH"),
+            FieldFunction::Path(keys) => write!(f, "{}", keys.iter().map(|key| format!("->\"{}\"", escape_string(key))).collect::<String>()),
+        }
+    }
 }
 
 pub trait Evaluable {
     fn evaluate(&self, r: &Record) -> Result<bool, Error>;
 }
 
+#[derive(Clone, Debug)]
 pub struct Comparison {
     pub field: String,
+    pub function: Option<FieldFunction>,
     pub op: CompOp,
     pub constant: IntermediateValue,
 }
 
 impl Comparison {
     pub fn new(field: String, op: CompOp, constant: IntermediateValue) -> Self {
-        Self { field, op, constant }
+        Self { field, function: None, op, constant }
+    }
+
+    pub fn with_function(field: String, function: FieldFunction, op: CompOp, constant: IntermediateValue) -> Self {
+        Self { field, function: Some(function), op, constant }
+    }
+
+    fn contains(&self, field_value: &Value) -> Result<bool, Error> {
+        match field_value {
+            Value::Json(Json::Array(items)) => {
+                for item in items {
+                    if compare_value_intermediate_value(&item.to_value(), &self.constant)? == Ordering::Equal {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            },
+            Value::List(items) => {
+                for item in items {
+                    if compare_value_intermediate_value(item, &self.constant)? == Ordering::Equal {
+                        return Ok(true);
+                    }
+                }
+                Ok(false)
+            },
+            other => Err(Error::TypeError(format!("CONTAINS expects a list but field '{}' is {:?}", self.field, other))),
+        }
     }
 }
 
@@ -33,21 +120,44 @@ impl Evaluable for Comparison {
         let field_value = r.values.get(&self.field)
             .ok_or_else(|| Error::MissingFieldError(format!("Missing field '{}' during evaluation", self.field)))?;
 
-        let comp_result = compare_value_intermediate_value(field_value, &self.constant)?;
+        // Only IS NULL and IS NOT NULL look at missing values; any other comparison with NULL matches nothing.
+        let is_null = matches!(field_value, Value::Null);
+        match (&self.op, &self.constant) {
+            (CompOp::Is, _) => return Ok(is_null),
+            (CompOp::IsNot, _) => return Ok(!is_null),
+            (op, IntermediateValue::Null) => return Err(Error::TypeError(format!("Cannot compare with NULL using {}; use IS NULL or IS NOT NULL", op))),
+            _ if is_null => return Ok(false),
+            _ => {},
+        }
+
+        let field_value = match &self.function {
+            Some(function) => &function.apply(field_value)?,
+            None => field_value,
+        };
+
+        if matches!(field_value, Value::Json(Json::Null)) && !matches!(self.constant, IntermediateValue::Json(_)) {
+            return Ok(false);
+        }
+
+        let ordering = || compare_value_intermediate_value(field_value, &self.constant);
 
         let result = match self.op {
-            CompOp::Eq => comp_result == Ordering::Equal,
-            CompOp::Neq => comp_result != Ordering::Equal,
-            CompOp::Leq => comp_result != Ordering::Greater,
-            CompOp::Lt => comp_result == Ordering::Less,
-            CompOp::Geq => comp_result != Ordering::Less,
-            CompOp::Gt => comp_result == Ordering::Greater,
+            CompOp::Eq => ordering()? == Ordering::Equal,
+            CompOp::Neq => ordering()? != Ordering::Equal,
+            CompOp::Leq => ordering()? != Ordering::Greater,
+            CompOp::Lt => ordering()? == Ordering::Less,
+            CompOp::Geq => ordering()? != Ordering::Less,
+            CompOp::Gt => ordering()? == Ordering::Greater,
+            CompOp::Contains => self.contains(field_value)?,
+            CompOp::Is => is_null,
+            CompOp::IsNot => !is_null,
         };
 
         Ok(result)
     }
 }
 
+#[derive(Clone, Debug)]
 pub struct ComparisonAnd {
     left: Box<AnyEvaluable>,
     right: Box<AnyEvaluable>,
@@ -65,6 +175,7 @@ impl Evaluable for ComparisonAnd {
     }
 }
 
+#[derive(Clone, Debug)]
 pub struct ComparisonOr {
     left: Box<AnyEvaluable>,
     right: Box<AnyEvaluable>,
@@ -82,12 +193,78 @@ impl Evaluable for ComparisonOr {
     }
 }
 
+#[derive(Clone, Debug)]
 pub enum AnyEvaluable {
     Comp(Comparison),
     And(ComparisonAnd),
     Or(ComparisonOr),
 }
 
+impl AnyEvaluable {
+    pub fn fields(&self) -> Vec<&String> {
+        match self {
+            AnyEvaluable::Comp(c) => vec![&c.field],
+            AnyEvaluable::And(c) => {
+                let mut fields = c.left.fields();
+                fields.extend(c.right.fields());
+                fields
+            },
+            AnyEvaluable::Or(c) => {
+                let mut fields = c.left.fields();
+                fields.extend(c.right.fields());
+                fields
+            },
+        }
+    }
+
+    pub fn candidates<T: Ord>(&self, lookup: &impl Fn(&Comparison) -> Option<BTreeSet<T>>) -> Option<BTreeSet<T>> {
+        match self {
+            AnyEvaluable::Comp(c) => lookup(c),
+            AnyEvaluable::And(c) => match (c.left.candidates(lookup), c.right.candidates(lookup)) {
+                (Some(mut left), Some(right)) => {
+                    left.retain(|candidate| right.contains(candidate));
+                    Some(left)
+                },
+                (Some(candidates), None) | (None, Some(candidates)) => Some(candidates),
+                (None, None) => None,
+            },
+            AnyEvaluable::Or(c) => {
+                let mut left = c.left.candidates(lookup)?;
+                left.extend(c.right.candidates(lookup)?);
+                Some(left)
+            },
+        }
+    }
+
+    pub fn values_mut(&mut self) -> Vec<&mut IntermediateValue> {
+        match self {
+            AnyEvaluable::Comp(c) => vec![&mut c.constant],
+            AnyEvaluable::And(c) => {
+                let mut values = c.left.values_mut();
+                values.extend(c.right.values_mut());
+                values
+            },
+            AnyEvaluable::Or(c) => {
+                let mut values = c.left.values_mut();
+                values.extend(c.right.values_mut());
+                values
+            },
+        }
+    }
+}
+
+impl Display for AnyEvaluable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnyEvaluable::Comp(Comparison { field, function: Some(FieldFunction::Path(keys)), op, constant }) => write!(f, "{} {} {}", format_json_path(field, keys), op, constant),
+            AnyEvaluable::Comp(Comparison { field, function: Some(function), op, constant }) => write!(f, "{}({}) {} {}", function, quote_ident(field), op, constant),
+            AnyEvaluable::Comp(c) => write!(f, "{} {} {}", quote_ident(&c.field), c.op, c.constant),
+            AnyEvaluable::And(c) => write!(f, "({} AND {})", c.left, c.right),
+            AnyEvaluable::Or(c) => write!(f, "({} OR {})", c.left, c.right),
+        }
+    }
+}
+
 impl Evaluable for AnyEvaluable {
     fn evaluate(&self, r: &Record) -> Result<bool, Error> {
         match self {
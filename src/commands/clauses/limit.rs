@@ -2,6 +2,7 @@ use crate::commands::clauses::clause::Clause;
 use crate::database::slice::TableSlice;
 use crate::errors::Error;
 
+#[derive(Clone, Debug)]
 pub struct  LimitClause {
     pub amount: usize
 }
@@ -17,4 +18,12 @@ impl Clause for LimitClause {
         slice.records.truncate(self.amount);
         Ok(slice)
     }
+
+    fn stage(&self) -> &'static str {
+        "Limit"
+    }
+
+    fn detail(&self) -> String {
+        self.amount.to_string()
+    }
 }
\ No newline at end of file
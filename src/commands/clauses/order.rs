@@ -1,8 +1,10 @@
-use crate::commands::clauses::clause::Clause;
+﻿use crate::commands::clauses::clause::Clause;
 use crate::database::slice::TableSlice;
 use crate::database::value::sort_compare_values;
 use crate::errors::Error;
+use crate::database::ident::quote_ident;
 
+#[derive(Clone, Debug)]
 pub struct OrderByClause {
     pub fields: Vec<String>,
 }
@@ -30,4 +32,12 @@ impl Clause for OrderByClause {
 
         Ok(slice)
     }
+
+    fn stage(&self) -> &'static str {
+        "Sort"
+    }
+
+    fn detail(&self) -> String {
+        self.fields.iter().map(|field| quote_ident(field)).collect::<Vec<_>>().join(", ")
+    }
 }
\ No newline at end of file
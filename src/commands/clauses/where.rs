@@ -3,6 +3,7 @@ use crate::commands::clauses::evaluable::{AnyEvaluable, Evaluable};
 use crate::database::slice::TableSlice;
 use crate::errors::Error;
 
+#[derive(Clone, Debug)]
 pub struct WhereClause {
     pub evaluation: AnyEvaluable,
 }
@@ -25,4 +26,12 @@ impl Clause for WhereClause {
 
         Ok(TableSlice::new(filtered))
     }
+
+    fn stage(&self) -> &'static str {
+        "Filter"
+    }
+
+    fn detail(&self) -> String {
+        self.evaluation.to_string()
+    }
 }
\ No newline at end of file
@@ -1,12 +1,20 @@
 ﻿use std::fmt::{Display, Formatter};
 use crate::commands::create::CreateCommand;
+use crate::commands::create_select::CreateSelectCommand;
 use crate::commands::delete::DeleteCommand;
+use crate::commands::describe::DescribeCommand;
+use crate::commands::explain::ExplainCommand;
+use crate::commands::index::CreateIndexCommand;
 use crate::commands::insert::InsertCommand;
+use crate::commands::insert_select::InsertSelectCommand;
 use crate::commands::read::ReadCommand;
 use crate::commands::save::SaveCommand;
 use crate::commands::select::SelectCommand;
+use crate::commands::show::ShowTablesCommand;
+use crate::commands::view::{CreateMaterializedViewCommand, CreateViewCommand, DropViewCommand, RefreshCommand};
 use crate::database::key::DatabaseKey;
 use crate::database::slice::SelectResult;
+use crate::database::table::InsertSummary;
 use crate::errors::Error;
 
 pub trait Command {
@@ -15,22 +23,42 @@ pub trait Command {
 
 pub enum AnyCommand<'a, K: DatabaseKey> {
     Create(CreateCommand<'a, K>),
+    CreateSelect(CreateSelectCommand<'a, K>),
+    CreateView(CreateViewCommand<'a, K>),
+    CreateIndex(CreateIndexCommand<'a, K>),
+    CreateMaterializedView(CreateMaterializedViewCommand<'a, K>),
     Delete(DeleteCommand<'a, K>),
+    Describe(DescribeCommand<'a, K>),
+    DropView(DropViewCommand<'a, K>),
+    Explain(ExplainCommand<'a, K>),
     Insert(InsertCommand<'a, K>),
+    InsertSelect(InsertSelectCommand<'a, K>),
     Read(ReadCommand<'a, K>),
+    Refresh(RefreshCommand<'a, K>),
     Save(SaveCommand<'a>),
     Select(SelectCommand<'a, K>),
+    ShowTables(ShowTablesCommand<'a, K>),
 }
 
 impl<K: DatabaseKey> Command for AnyCommand<'_, K> {
     fn execute(&mut self) -> Result<ExecutionSuccessValue, Error> {
         match self { 
             AnyCommand::Create(c) => c.execute(),
+            AnyCommand::CreateSelect(c) => c.execute(),
+            AnyCommand::CreateView(c) => c.execute(),
+            AnyCommand::CreateIndex(c) => c.execute(),
+            AnyCommand::CreateMaterializedView(c) => c.execute(),
             AnyCommand::Delete(c) => c.execute(),
+            AnyCommand::Describe(c) => c.execute(),
+            AnyCommand::DropView(c) => c.execute(),
+            AnyCommand::Explain(c) => c.execute(),
             AnyCommand::Insert(c) => c.execute(),
+            AnyCommand::InsertSelect(c) => c.execute(),
             AnyCommand::Read(c) => c.execute(),
+            AnyCommand::Refresh(c) => c.execute(),
             AnyCommand::Save(c) => c.execute(),
             AnyCommand::Select(c) => c.execute(),
+            AnyCommand::ShowTables(c) => c.execute(),
         }
     }
 }
@@ -39,6 +67,21 @@ pub enum ExecutionSuccessValue {
     Success(String),
     SuccessFileOperation(String),
     SelectResult(SelectResult),
+    TableCreated { name: String, records: Option<usize> },
+    Inserted(InsertSummary),
+    Deleted { key: String, rows: usize },
+}
+
+impl ExecutionSuccessValue {
+    pub fn affected_rows(&self) -> usize {
+        match self {
+            ExecutionSuccessValue::TableCreated { records, .. } => records.unwrap_or(0),
+            ExecutionSuccessValue::Inserted(summary) => summary.inserted + summary.replaced + summary.updated,
+            ExecutionSuccessValue::Deleted { rows, .. } => *rows,
+            ExecutionSuccessValue::SelectResult(r) => r.rows.len(),
+            ExecutionSuccessValue::Success(_) | ExecutionSuccessValue::SuccessFileOperation(_) => 0,
+        }
+    }
 }
 
 impl Display for ExecutionSuccessValue {
@@ -47,6 +90,15 @@ impl Display for ExecutionSuccessValue {
             ExecutionSuccessValue::Success(msg) => writeln!(f, "{}", msg),
             ExecutionSuccessValue::SuccessFileOperation(msg) => writeln!(f, "{}", msg),
             ExecutionSuccessValue::SelectResult(r) => writeln!(f, "{}", r),
+            ExecutionSuccessValue::TableCreated { name, records: None } =>
+                writeln!(f, "Table '{}' created successfully", name),
+            ExecutionSuccessValue::TableCreated { name, records: Some(records) } =>
+                writeln!(f, "Table '{}' created successfully with {} records", name, records),
+            ExecutionSuccessValue::Inserted(InsertSummary { inserted: 1, replaced: 0, updated: 0, skipped: 0, assigned_keys }) if assigned_keys.is_empty() =>
+                writeln!(f, "Successfully inserted record"),
+            ExecutionSuccessValue::Inserted(summary) => writeln!(f, "{}", summary),
+            ExecutionSuccessValue::Deleted { key, rows: 1 } => writeln!(f, "Successfully deleted record {} from table", key),
+            ExecutionSuccessValue::Deleted { key, rows } => writeln!(f, "Successfully deleted record {} from table and {} referencing records", key, rows - 1),
         }
     }
 }
@@ -1,5 +1,6 @@
 ﻿use std::collections::HashMap;
 use crate::commands::command::{Command, ExecutionSuccessValue};
+use crate::database::constraint::Constraint;
 use crate::database::databases::Database;
 use crate::database::key::DatabaseKey;
 use crate::database::table::Table;
@@ -9,13 +10,24 @@ use crate::errors::{self, Error};
 pub struct CreateCommand<'a, K: DatabaseKey> {
     database: &'a mut Database<K>,
     name: String,
-    key: String,
+    key: Vec<String>,
     fields: Vec<(String, FieldType)>,
+    auto_increment: bool,
+    constraints: Vec<Constraint>,
+    nullable: Vec<String>,
 }
 
 impl<'a, K: DatabaseKey> CreateCommand<'a, K> {
-    pub fn new(database: &'a mut Database<K>, name: String, key: String, fields: Vec<(String, FieldType)>) -> Self {
-        Self { database, name, key, fields }
+    pub fn new(database: &'a mut Database<K>, name: String, key: Vec<String>, fields: Vec<(String, FieldType)>) -> Self {
+        Self { database, name, key, fields, auto_increment: false, constraints: Vec::new(), nullable: Vec::new() }
+    }
+
+    pub fn with_options(self, auto_increment: bool, constraints: Vec<Constraint>) -> Self {
+        Self { auto_increment, constraints, ..self }
+    }
+
+    pub fn with_nullable(self, nullable: Vec<String>) -> Self {
+        Self { nullable, ..self }
     }
 
     fn create_fields(&self) -> Result<HashMap<String, FieldType>, Error> {
@@ -37,11 +49,23 @@ impl<K: DatabaseKey> Command for CreateCommand<'_, K> {
         let key = self.key.clone();
         let fields = self.create_fields()?;
 
-        let table = Table::new(key, fields, HashMap::new());
+        let mut table = Table::new(key, fields, HashMap::new())?;
+
+        if self.auto_increment {
+            table.enable_auto_increment()?;
+        }
+
+        for field in &self.nullable {
+            table.set_nullable(field)?;
+        }
+
+        for constraint in &self.constraints {
+            table.add_constraint(constraint.clone())?;
+        }
 
         self.database.add_table(self.name.clone(), table)?;
 
-        Ok(ExecutionSuccessValue::Success(format!("Table '{}' created successfully", self.name)))
+        Ok(ExecutionSuccessValue::TableCreated { name: self.name.clone(), records: None })
     }
 }
 
@@ -49,15 +73,16 @@ impl<K: DatabaseKey> Command for CreateCommand<'_, K> {
 mod tests {
     use super::*;
     use crate::commands::command::{Command, ExecutionSuccessValue};
+    use crate::database::key::AnyKey;
 
     #[test]
     fn test_create_command() {
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
 
         let mut cmd = CreateCommand::new(
             &mut db,
             "library".to_string(),
-            "id".to_string(),
+            vec!["id".to_string()],
             vec![
                 ("id".to_string(), FieldType::String),
                 ("year".to_string(), FieldType::Int)
@@ -65,7 +90,8 @@ mod tests {
         );
 
         let result = cmd.execute().unwrap();
-        assert!(matches!(result, ExecutionSuccessValue::Success(_)));
+        assert!(matches!(result, ExecutionSuccessValue::TableCreated { records: None, .. }));
+        assert_eq!(result.to_string(), "Table 'library' created successfully\n");
         assert!(db.get_table(&"library".to_string()).is_ok());
     }
 }
@@ -0,0 +1,152 @@
+﻿use std::collections::HashMap;
+use crate::commands::clauses::clause::AnyClause;
+use crate::commands::command::{Command, ExecutionSuccessValue};
+use crate::commands::select::SelectCommand;
+use crate::database::databases::Database;
+use crate::database::key::DatabaseKey;
+use crate::database::record::Projection;
+use crate::database::slice::SelectResult;
+use crate::database::source::SelectSource;
+use crate::database::table::{OnConflict, Table};
+use crate::database::types::FieldType;
+use crate::errors::{self, Error};
+
+pub struct CreateSelectCommand<'a, K: DatabaseKey> {
+    database: &'a mut Database<K>,
+    name: String,
+    key: Vec<String>,
+    source: String,
+    fields: Vec<Projection>,
+    clauses: Vec<AnyClause>,
+}
+
+impl<'a, K: DatabaseKey> CreateSelectCommand<'a, K> {
+    pub fn new(database: &'a mut Database<K>, name: String, key: Vec<String>, source: String, fields: Vec<Projection>, clauses: Vec<AnyClause>) -> Self {
+        Self { database, name, key, source, fields, clauses }
+    }
+
+    fn infer_fields(fields: &[Projection], result: &SelectResult, source: &SelectSource<'_, K>) -> Result<HashMap<String, FieldType>, Error> {
+        let mut fields_map = HashMap::new();
+
+        for (index, projection) in fields.iter().enumerate() {
+            let name = projection.to_string();
+
+            if fields_map.contains_key(&name) {
+                return Err(Error::AlreadyExistsError(format!("Field '{}' is selected more than once", name)));
+            }
+
+            let source_type = source.fields().get(&projection.field).filter(|_| projection.path.is_empty());
+
+            let inferred = result.rows.iter().find_map(|row| row.values[index].1.get_field_type());
+
+            let field_type = match (source_type, inferred) {
+                (Some(field_type), _) => field_type.clone(),
+                (None, Some(field_type)) => field_type,
+                (None, None) => return Err(Error::MissingFieldError(format!("Field '{}' does not exist", name))),
+            };
+
+            fields_map.insert(name, field_type);
+        }
+
+        Ok(fields_map)
+    }
+
+    pub fn build_table(database: &Database<K>, key: Vec<String>, source_name: &String, projections: &[Projection], clauses: &[AnyClause]) -> Result<Table<K>, Error> {
+        let source = database.get_source(source_name)?;
+        let result = SelectCommand::run(&source, projections, clauses)?;
+        let fields = Self::infer_fields(projections, &result, &source)?;
+
+        let records = result.rows
+            .iter()
+            .map(|row| row.to_intermediate_record())
+            .collect::<Vec<_>>();
+
+        // Plain fields stay NULL-able when they are declared NULL in the source.
+        let nullable = projections
+            .iter()
+            .filter(|projection| projection.path.is_empty() && database.is_source_field_nullable(source_name, &projection.field))
+            .map(|projection| projection.to_string())
+            .collect::<Vec<_>>();
+
+        let mut table = Table::new(key, fields, HashMap::new())?;
+        for field in &nullable {
+            table.set_nullable(field)?;
+        }
+        table.add_records(records, &OnConflict::Fail)?;
+
+        Ok(table)
+    }
+}
+
+impl<K: DatabaseKey> Command for CreateSelectCommand<'_, K> {
+    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
+        if self.database.has_table(&self.name) {
+            return Err(Error::AlreadyExistsError(format!("Table '{}' already exists", self.name)));
+        }
+
+        let table = Self::build_table(self.database, self.key.clone(), &self.source, &self.fields, &self.clauses)?;
+        let records = table.len();
+
+        self.database.add_table(self.name.clone(), table)?;
+
+        Ok(ExecutionSuccessValue::TableCreated { name: self.name.clone(), records: Some(records) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::clauses::evaluable::{AnyEvaluable, CompOp, Comparison};
+    use crate::commands::clauses::r#where::WhereClause;
+    use crate::commands::fixtures::create_books;
+    use crate::database::key::AnyKey;
+    use crate::database::value::IntermediateValue;
+    use super::*;
+
+    #[test]
+    fn test_create_select_command() {
+        let mut db = Database::<AnyKey>::new();
+        create_books(&mut db, vec![("title", FieldType::String, IntermediateValue::String("Untitled".to_string()))]);
+
+        let mut cmd = CreateSelectCommand::new(
+            &mut db,
+            "archive".to_string(),
+            vec!["id".to_string()],
+            "books".to_string(),
+            vec!["id".into(), "title".into()],
+            vec![AnyClause::Where(WhereClause::new(AnyEvaluable::Comp(
+                Comparison::new("year".to_string(), CompOp::Lt, IntermediateValue::Numeric(1990f64))
+            )))]
+        );
+
+        let result = cmd.execute().unwrap();
+        assert!(matches!(result, ExecutionSuccessValue::TableCreated { records: Some(2), .. }));
+
+        let archive = db.get_table(&"archive".to_string()).unwrap();
+        assert_eq!(archive.len(), 2);
+        assert_eq!(archive.fields().get("title"), Some(&FieldType::String));
+        assert!(!archive.fields().contains_key("year"));
+    }
+
+    #[test]
+    fn test_create_select_command_empty_result() {
+        let mut db = Database::<AnyKey>::new();
+        create_books(&mut db, vec![("title", FieldType::String, IntermediateValue::String("Untitled".to_string()))]);
+
+        let mut cmd = CreateSelectCommand::new(
+            &mut db,
+            "archive".to_string(),
+            vec!["id".to_string()],
+            "books".to_string(),
+            vec!["id".into(), "year".into()],
+            vec![AnyClause::Where(WhereClause::new(AnyEvaluable::Comp(
+                Comparison::new("year".to_string(), CompOp::Lt, IntermediateValue::Numeric(1900f64))
+            )))]
+        );
+
+        cmd.execute().unwrap();
+
+        let archive = db.get_table(&"archive".to_string()).unwrap();
+        assert!(archive.is_empty());
+        assert_eq!(archive.fields().get("year"), Some(&FieldType::Int));
+    }
+}
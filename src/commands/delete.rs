@@ -1,26 +1,38 @@
 ﻿use crate::commands::command::{Command, ExecutionSuccessValue};
+use crate::database::databases::Database;
 use crate::database::key::DatabaseKey;
-use crate::database::table::Table;
+use crate::database::slice::SelectResult;
 use crate::database::value::KeyValue;
 use crate::errors;
 
 pub struct DeleteCommand<'a, K: DatabaseKey> {
-    table: &'a mut Table<K>,
-    key: KeyValue
+    database: &'a mut Database<K>,
+    table: String,
+    key: Vec<KeyValue>,
+    returning: Option<Vec<String>>,
 }
 
 impl<'a, K: DatabaseKey> DeleteCommand<'a, K> {
-    pub fn new(table: &'a mut Table<K>, key: KeyValue) -> Self {
-        Self { table, key }
+    pub fn new(database: &'a mut Database<K>, table: String, key: Vec<KeyValue>, returning: Option<Vec<String>>) -> Self {
+        Self { database, table, key, returning }
     }
 }
 
 impl<K: DatabaseKey> Command for DeleteCommand<'_, K> {
     fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
-        let typed_key = K::from_key_value(&self.key)?;
-        self.table.delete_record(&typed_key)?;
-        
-        Ok(ExecutionSuccessValue::Success(format!("Successfully deleted record {} from table", typed_key)))
+        let table = self.database.get_table(&self.table)?;
+        let typed_key = table.typed_key(&self.key)?;
+
+        if let Some(returning) = &self.returning {
+            table.validate_fields(returning)?;
+        }
+
+        let (record, rows) = self.database.delete_record(&self.table, &typed_key)?;
+
+        match &self.returning {
+            Some(returning) => Ok(ExecutionSuccessValue::SelectResult(SelectResult::from_fields(&[record], returning)?)),
+            None => Ok(ExecutionSuccessValue::Deleted { key: typed_key.to_string(), rows }),
+        }
     }
 }
 
@@ -28,19 +40,20 @@ impl<K: DatabaseKey> Command for DeleteCommand<'_, K> {
 mod tests{
     use crate::commands::create::CreateCommand;
     use crate::commands::insert::InsertCommand;
-    use crate::database::databases::Database;
+    use crate::database::key::AnyKey;
+    use crate::database::table::OnConflict;
     use crate::database::types::FieldType;
     use crate::database::value::IntermediateValue;
     use super::*;
 
     #[test]
     fn test_delete_command() {
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
 
         let mut cmd = CreateCommand::new(
             &mut db,
             "library".to_string(),
-            "id".to_string(),
+            vec!["id".to_string()],
             vec![
                 ("id".to_string(), FieldType::String),
                 ("year".to_string(), FieldType::Int)
@@ -49,22 +62,28 @@ mod tests{
         cmd.execute().unwrap();
 
         let mut cmd = InsertCommand::new(
-            db.get_table(&"library".to_string()).unwrap(),
+            &mut db,
+            "library".to_string(),
             vec![
                 ("id".to_string(), IntermediateValue::String("1".to_string())),
                 ("year".to_string(), IntermediateValue::Numeric(2000f64)),
-            ]
+            ],
+            OnConflict::Fail,
+            None
         );
         cmd.execute().unwrap();
 
         let mut cmd = DeleteCommand::new(
-            db.get_table(&"library".to_string()).unwrap(),
-            KeyValue::String("1".to_string())
+            &mut db,
+            "library".to_string(),
+            vec![KeyValue::String("1".to_string())],
+            None
         );
 
         let result = cmd.execute().unwrap();
 
-        assert!(matches!(result, ExecutionSuccessValue::Success(_)));
+        assert!(matches!(result, ExecutionSuccessValue::Deleted { rows: 1, .. }));
+        assert_eq!(result.to_string(), "Successfully deleted record 1 from table\n");
         assert_eq!(db.get_table(&"library".to_string()).unwrap().len(), 0)
     }
 }
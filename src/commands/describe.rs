@@ -0,0 +1,95 @@
+﻿use crate::commands::command::{Command, ExecutionSuccessValue};
+use crate::database::databases::Database;
+use crate::database::key::DatabaseKey;
+use crate::database::slice::{SelectResult, SelectRow};
+use crate::database::types::FieldType;
+use crate::database::value::Value;
+use crate::errors;
+use crate::errors::Error;
+
+pub struct DescribeCommand<'a, K: DatabaseKey> {
+    database: &'a Database<K>,
+    name: String,
+}
+
+impl<'a, K: DatabaseKey> DescribeCommand<'a, K> {
+    pub fn new(database: &'a Database<K>, name: String) -> Self {
+        Self { database, name }
+    }
+}
+
+/// Fields of a table, view or catalog table with the key fields first.
+pub fn describe_fields<K: DatabaseKey>(database: &Database<K>, name: &String) -> Result<Vec<(String, FieldType, bool)>, Error> {
+    let key = database.tables().get(name).map(|table| table.key().clone()).unwrap_or_default();
+    let key_position = |field: &String| key.iter().position(|key_field| key_field == field).unwrap_or(key.len());
+
+    let mut fields = database.source_fields(name)?.into_iter().collect::<Vec<_>>();
+    fields.sort_by(|(a, _), (b, _)| key_position(a).cmp(&key_position(b)).then(a.cmp(b)));
+
+    Ok(fields
+        .into_iter()
+        .map(|(field, field_type)| {
+            let is_key = key.contains(&field);
+            (field, field_type, is_key)
+        })
+        .collect())
+}
+
+impl<K: DatabaseKey> Command for DescribeCommand<'_, K> {
+    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
+        let rows = self.database.get_source(&self.name)?.len();
+
+        let rows = describe_fields(self.database, &self.name)?
+            .into_iter()
+            .map(|(name, field_type, key)| {
+                let nullable = self.database.is_source_field_nullable(&self.name, &name);
+
+                SelectRow {
+                    values: vec![
+                        ("field".to_string(), Value::String(name)),
+                        ("type".to_string(), Value::String(field_type.to_string())),
+                        ("key".to_string(), Value::Bool(key)),
+                        ("nullable".to_string(), Value::Bool(nullable)),
+                        ("rows".to_string(), Value::Int(rows as i64)),
+                    ]
+                }
+            })
+            .collect();
+
+        Ok(ExecutionSuccessValue::SelectResult(SelectResult { rows }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::create::CreateCommand;
+    use crate::database::key::AnyKey;
+    use crate::database::types::FieldType;
+    use super::*;
+
+    #[test]
+    fn test_describe_command() {
+        let mut db = Database::<AnyKey>::new();
+
+        let mut cmd = CreateCommand::new(
+            &mut db,
+            "library".to_string(),
+            vec!["id".to_string()],
+            vec![
+                ("year".to_string(), FieldType::Int),
+                ("id".to_string(), FieldType::String),
+                ("available".to_string(), FieldType::Bool)
+            ]
+        );
+        cmd.execute().unwrap();
+
+        let mut cmd = DescribeCommand::new(&db, "library".to_string());
+
+        let result = cmd.execute().unwrap();
+
+        assert_eq!(result.to_string(), "field: \"id\", type: \"String\", key: true, nullable: false, rows: 0
+field: \"available\", type: \"Bool\", key: false, nullable: false, rows: 0
+field: \"year\", type: \"Int\", key: false, nullable: false, rows: 0
+");
+    }
+}
@@ -0,0 +1,180 @@
+﻿use std::time::Instant;
+use crate::commands::clauses::clause::{AnyClause, Clause};
+use crate::commands::command::{Command, ExecutionSuccessValue};
+use crate::database::key::DatabaseKey;
+use crate::database::record::Projection;
+use crate::database::slice::{SelectResult, SelectRow};
+use crate::database::source::SelectSource;
+use crate::database::value::Value;
+use crate::errors;
+
+struct Stage {
+    name: &'static str,
+    detail: String,
+    rows_in: usize,
+    rows_out: usize,
+    time_us: u128,
+}
+
+impl Stage {
+    fn new(name: &'static str, detail: String) -> Self {
+        Self { name, detail, rows_in: 0, rows_out: 0, time_us: 0 }
+    }
+
+    fn to_row(&self, analyze: bool) -> SelectRow {
+        let mut values = vec![
+            ("stage".to_string(), Value::String(self.name.to_string())),
+            ("detail".to_string(), Value::String(self.detail.clone())),
+        ];
+
+        if analyze {
+            values.push(("rows_in".to_string(), Value::Int(self.rows_in as i64)));
+            values.push(("rows_out".to_string(), Value::Int(self.rows_out as i64)));
+            values.push(("time_us".to_string(), Value::Int(self.time_us as i64)));
+        }
+
+        SelectRow { values }
+    }
+}
+
+pub struct ExplainCommand<'a, K: DatabaseKey> {
+    source: SelectSource<'a, K>,
+    table_name: String,
+    fields: Vec<Projection>,
+    clauses: Vec<AnyClause>,
+    analyze: bool,
+}
+
+impl<'a, K: DatabaseKey> ExplainCommand<'a, K> {
+    pub fn new(source: SelectSource<'a, K>, table_name: String, fields: Vec<Projection>, clauses: Vec<AnyClause>, analyze: bool) -> Self {
+        Self { source, table_name, fields, clauses, analyze }
+    }
+
+    fn plan(&self) -> Vec<Stage> {
+        let mut stages = vec![Stage::new("Scan", format!("{} of '{}'", self.source.scan_kind(&self.clauses), self.table_name))];
+
+        for clause in &self.clauses {
+            stages.push(Stage::new(clause.stage(), clause.detail()));
+        }
+
+        stages.push(Stage::new("Project", self.fields.iter().map(|field| field.to_string()).collect::<Vec<_>>().join(", ")));
+
+        stages
+    }
+
+    fn analyze(&self, stages: &mut [Stage]) -> Result<(), errors::Error> {
+        let start = Instant::now();
+        let mut slice = self.source.scan(&self.clauses);
+        stages[0].rows_in = self.source.len();
+        stages[0].rows_out = slice.records.len();
+        stages[0].time_us = start.elapsed().as_micros();
+
+        for (clause, stage) in self.clauses.iter().zip(stages[1..].iter_mut()) {
+            let start = Instant::now();
+            stage.rows_in = slice.records.len();
+            slice = clause.apply(slice)?;
+            stage.rows_out = slice.records.len();
+            stage.time_us = start.elapsed().as_micros();
+        }
+
+        let start = Instant::now();
+        let result = SelectResult::from_records(&slice.records, &self.fields)?;
+        if let Some(project) = stages.last_mut() {
+            project.rows_in = slice.records.len();
+            project.rows_out = result.rows.len();
+            project.time_us = start.elapsed().as_micros();
+        }
+
+        Ok(())
+    }
+}
+
+impl<K: DatabaseKey> Command for ExplainCommand<'_, K> {
+    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
+        let mut stages = self.plan();
+
+        if self.analyze {
+            self.analyze(&mut stages)?;
+        }
+
+        let rows = stages.iter().map(|stage| stage.to_row(self.analyze)).collect();
+
+        Ok(ExecutionSuccessValue::SelectResult(SelectResult { rows }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::clauses::evaluable::{AnyEvaluable, CompOp, Comparison};
+    use crate::commands::clauses::limit::LimitClause;
+    use crate::commands::clauses::order::OrderByClause;
+    use crate::commands::clauses::r#where::WhereClause;
+    use crate::commands::fixtures::create_books;
+    use crate::database::databases::Database;
+    use crate::database::key::AnyKey;
+    use crate::database::value::IntermediateValue;
+    use super::*;
+
+    fn clauses() -> Vec<AnyClause> {
+        vec![
+            AnyClause::Where(WhereClause::new(AnyEvaluable::Comp(
+                Comparison::new("year".to_string(), CompOp::Lt, IntermediateValue::Numeric(1990f64))
+            ))),
+            AnyClause::Order(OrderByClause::new(vec!["year".to_string()])),
+            AnyClause::Limit(LimitClause::new(1)),
+        ]
+    }
+
+    #[test]
+    fn test_explain_command() {
+        let mut db = Database::<AnyKey>::new();
+        create_books(&mut db, Vec::new());
+
+        let mut cmd = ExplainCommand::new(
+            db.get_source(&"books".to_string()).unwrap(),
+            "books".to_string(),
+            vec!["id".into()],
+            clauses(),
+            false
+        );
+
+        let result = cmd.execute().unwrap();
+
+        assert_eq!(result.to_string(), "stage: \"Scan\", detail: \"full scan of 'books'\"
+stage: \"Filter\", detail: \"year < 1990\"
+stage: \"Sort\", detail: \"year\"
+stage: \"Limit\", detail: \"1\"
+stage: \"Project\", detail: \"id\"
+");
+    }
+
+    #[test]
+    fn test_explain_analyze_command() {
+        let mut db = Database::<AnyKey>::new();
+        create_books(&mut db, Vec::new());
+
+        let mut cmd = ExplainCommand::new(
+            db.get_source(&"books".to_string()).unwrap(),
+            "books".to_string(),
+            vec!["id".into()],
+            clauses(),
+            true
+        );
+
+        let result = cmd.execute().unwrap();
+
+        if let ExecutionSuccessValue::SelectResult(r) = result {
+            let counts = r.rows
+                .iter()
+                .map(|row| match (&row.values[2].1, &row.values[3].1) {
+                    (Value::Int(rows_in), Value::Int(rows_out)) => (*rows_in, *rows_out),
+                    _ => panic!("Expected row counts"),
+                })
+                .collect::<Vec<_>>();
+
+            assert_eq!(counts, vec![(3, 3), (3, 2), (2, 2), (2, 1), (1, 1)]);
+        } else {
+            panic!("Expected a select result");
+        }
+    }
+}
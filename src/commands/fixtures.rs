@@ -0,0 +1,33 @@
+﻿use crate::commands::command::Command;
+use crate::commands::create::CreateCommand;
+use crate::commands::insert::InsertCommand;
+use crate::database::databases::Database;
+use crate::database::key::AnyKey;
+use crate::database::table::OnConflict;
+use crate::database::types::FieldType;
+use crate::database::value::IntermediateValue;
+
+/// Creates `books` keyed by `id` with the years 1985, 1999 and 1970; every extra field gets the same value in each row.
+pub fn create_books(db: &mut Database<AnyKey>, extra_fields: Vec<(&str, FieldType, IntermediateValue)>) {
+    let fields = [("id", FieldType::Int), ("year", FieldType::Int)]
+        .into_iter()
+        .chain(extra_fields.iter().map(|(name, field_type, _)| (*name, field_type.clone())))
+        .map(|(name, field_type)| (name.to_string(), field_type))
+        .collect();
+
+    let mut cmd = CreateCommand::new(db, "books".to_string(), vec!["id".to_string()], fields);
+    cmd.execute().unwrap();
+
+    for (id, year) in [(1, 1985), (2, 1999), (3, 1970)] {
+        let values = [
+            ("id".to_string(), IntermediateValue::Numeric(id as f64)),
+            ("year".to_string(), IntermediateValue::Numeric(year as f64)),
+        ]
+            .into_iter()
+            .chain(extra_fields.iter().map(|(name, _, value)| (name.to_string(), value.clone())))
+            .collect();
+
+        let mut cmd = InsertCommand::new(db, "books".to_string(), values, OnConflict::Fail, None);
+        cmd.execute().unwrap();
+    }
+}
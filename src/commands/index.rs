@@ -0,0 +1,102 @@
+﻿use crate::commands::command::{Command, ExecutionSuccessValue};
+use crate::database::databases::Database;
+use crate::database::key::DatabaseKey;
+use crate::errors;
+
+pub struct CreateIndexCommand<'a, K: DatabaseKey> {
+    database: &'a mut Database<K>,
+    table: String,
+    field: String,
+}
+
+impl<'a, K: DatabaseKey> CreateIndexCommand<'a, K> {
+    pub fn new(database: &'a mut Database<K>, table: String, field: String) -> Self {
+        Self { database, table, field }
+    }
+}
+
+impl<K: DatabaseKey> Command for CreateIndexCommand<'_, K> {
+    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
+        self.database.get_table(&self.table)?.add_index(&self.field)?;
+
+        Ok(ExecutionSuccessValue::Success(format!("Index on '{}' ({}) created successfully", self.table, self.field)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::clauses::clause::AnyClause;
+    use crate::commands::clauses::evaluable::{AnyEvaluable, CompOp, Comparison, ComparisonAnd};
+use crate::commands::clauses::limit::LimitClause;
+    use crate::commands::clauses::r#where::WhereClause;
+    use crate::commands::fixtures::create_books;
+    use crate::commands::select::SelectCommand;
+    use crate::database::key::{AnyKey, TableKey};
+    use crate::database::types::FieldType;
+    use crate::database::value::IntermediateValue;
+    use crate::errors::Error;
+    use super::*;
+
+    #[test]
+    fn test_create_index_command() {
+        let mut db = Database::<AnyKey>::new();
+
+        create_books(&mut db, vec![("tags", FieldType::List(Box::new(FieldType::String)), IntermediateValue::List(Vec::new()))]);
+
+        let mut cmd = CreateIndexCommand::new(&mut db, "books".to_string(), "year".to_string());
+        cmd.execute().unwrap();
+
+        let mut cmd = CreateIndexCommand::new(&mut db, "books".to_string(), "year".to_string());
+        assert!(matches!(cmd.execute(), Err(Error::AlreadyExistsError(_))));
+
+        let mut cmd = CreateIndexCommand::new(&mut db, "books".to_string(), "missing".to_string());
+        assert!(matches!(cmd.execute(), Err(Error::MissingFieldError(_))));
+
+        let mut cmd = CreateIndexCommand::new(&mut db, "books".to_string(), "tags".to_string());
+        assert!(matches!(cmd.execute(), Err(Error::TypeError(_))));
+
+        db.get_table(&"books".to_string()).unwrap().delete_record(&TableKey(vec![AnyKey::Int(3)])).unwrap();
+
+        let clauses = vec![AnyClause::Where(WhereClause::new(AnyEvaluable::Comp(
+            Comparison::new("year".to_string(), CompOp::Lt, IntermediateValue::Numeric(1990f64))
+        )))];
+
+        let source = db.get_source(&"books".to_string()).unwrap();
+        assert_eq!(source.scan_kind(&clauses), "index scan");
+        assert_eq!(source.scan(&clauses).records.len(), 1);
+
+        let result = SelectCommand::run(&source, &["id".into()], &clauses).unwrap();
+        assert_eq!(result.rows.len(), 1);
+    }
+
+    #[test]
+    fn test_index_scan_uses_later_conditions() {
+        let mut db = Database::<AnyKey>::new();
+        create_books(&mut db, vec![]);
+
+        let mut cmd = CreateIndexCommand::new(&mut db, "books".to_string(), "year".to_string());
+        cmd.execute().unwrap();
+
+        let unindexed = || AnyEvaluable::Comp(Comparison::new("id".to_string(), CompOp::Gt, IntermediateValue::Numeric(0f64)));
+        let indexed = || AnyEvaluable::Comp(Comparison::new("year".to_string(), CompOp::Lt, IntermediateValue::Numeric(1990f64)));
+
+        let source = db.get_source(&"books".to_string()).unwrap();
+
+        let and = vec![AnyClause::Where(WhereClause::new(AnyEvaluable::And(ComparisonAnd::new(unindexed(), indexed()))))];
+        assert_eq!(source.scan_kind(&and), "index scan");
+        assert_eq!(source.scan(&and).records.len(), 2);
+
+        let later = vec![
+            AnyClause::Where(WhereClause::new(unindexed())),
+            AnyClause::Where(WhereClause::new(indexed())),
+        ];
+        assert_eq!(source.scan_kind(&later), "index scan");
+        assert_eq!(SelectCommand::run(&source, &["id".into()], &later).unwrap().rows.len(), 2);
+
+        let after_limit = vec![
+            AnyClause::Limit(LimitClause::new(1)),
+            AnyClause::Where(WhereClause::new(indexed())),
+        ];
+        assert_eq!(source.scan_kind(&after_limit), "full scan");
+    }
+}
@@ -1,20 +1,25 @@
 ﻿use std::collections::HashMap;
 use crate::commands::command::{Command, ExecutionSuccessValue};
+use crate::database::databases::Database;
 use crate::database::key::DatabaseKey;
 use crate::database::record::IntermediateRecord;
-use crate::database::table::Table;
+use crate::database::slice::SelectResult;
+use crate::database::table::OnConflict;
 use crate::database::value::IntermediateValue;
 use crate::errors;
 use crate::errors::Error;
 
 pub struct InsertCommand<'a, K: DatabaseKey> {
-    table: &'a mut Table<K>,
+    database: &'a mut Database<K>,
+    table: String,
     assignments: Vec<(String, IntermediateValue)>,
+    on_conflict: OnConflict,
+    returning: Option<Vec<String>>,
 }
 
 impl<'a, K: DatabaseKey> InsertCommand<'a, K> {
-    pub fn new(table: &'a mut Table<K>, fields: Vec<(String, IntermediateValue)>) -> Self {
-        Self { table, assignments: fields }
+    pub fn new(database: &'a mut Database<K>, table: String, fields: Vec<(String, IntermediateValue)>, on_conflict: OnConflict, returning: Option<Vec<String>>) -> Self {
+        Self { database, table, assignments: fields, on_conflict, returning }
     }
     
     pub fn create_assignments(&self) -> Result<HashMap<String, IntermediateValue>, Error> {
@@ -37,27 +42,36 @@ impl<K: DatabaseKey> Command for InsertCommand<'_, K> {
         
         let record = IntermediateRecord { values: assignments };
         
-        self.table.add_record(record)?;
-        
-        Ok(ExecutionSuccessValue::Success("Successfully inserted record".to_string()))
+        if let Some(returning) = &self.returning {
+            self.database.get_table(&self.table)?.validate_fields(returning)?;
+        }
+
+        let (summary, written) = self.database.insert_records(&self.table, vec![record], &self.on_conflict)?;
+
+        match &self.returning {
+            Some(returning) => Ok(ExecutionSuccessValue::SelectResult(SelectResult::from_fields(&written, returning)?)),
+            None => Ok(ExecutionSuccessValue::Inserted(summary)),
+        }
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::commands::create::CreateCommand;
-    use crate::database::databases::Database;
+    use crate::database::key::AnyKey;
     use crate::database::types::FieldType;
+    use crate::database::table::InsertSummary;
+    use crate::database::value::Value;
     use super::*;
 
     #[test]
     fn test_insert_command() {
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
 
         let mut cmd = CreateCommand::new(
             &mut db,
             "library".to_string(),
-            "id".to_string(),
+            vec!["id".to_string()],
             vec![
                 ("id".to_string(), FieldType::String),
                 ("year".to_string(), FieldType::Int)
@@ -66,16 +80,76 @@ mod test {
         cmd.execute().unwrap();
 
         let mut cmd = InsertCommand::new(
-            db.get_table(&"library".to_string()).unwrap(),
+            &mut db,
+            "library".to_string(),
             vec![
                 ("id".to_string(), IntermediateValue::String("1".to_string())),
                 ("year".to_string(), IntermediateValue::Numeric(2000f64)),
-            ]
+            ],
+            OnConflict::Fail,
+            None
         );
 
         let result = cmd.execute().unwrap();
 
-        assert!(matches!(result, ExecutionSuccessValue::Success(_)));
+        assert!(matches!(result, ExecutionSuccessValue::Inserted(InsertSummary { inserted: 1, .. })));
         assert_eq!(db.get_table(&"library".to_string()).unwrap().len(), 1)
     }
+
+    #[test]
+    fn test_insert_command_on_conflict() {
+        let mut db = Database::<AnyKey>::new();
+
+        let mut cmd = CreateCommand::new(
+            &mut db,
+            "library".to_string(),
+            vec!["id".to_string()],
+            vec![
+                ("id".to_string(), FieldType::String),
+                ("year".to_string(), FieldType::Int)
+            ]
+        );
+        cmd.execute().unwrap();
+
+        let record = vec![
+            ("id".to_string(), IntermediateValue::String("1".to_string())),
+            ("year".to_string(), IntermediateValue::Numeric(2000f64)),
+        ];
+
+        let cases = [
+            (OnConflict::Fail, "Successfully inserted record"),
+            (OnConflict::Ignore, "Inserted 0, replaced 0, updated 0 and skipped 1 records"),
+            (OnConflict::Replace, "Inserted 0, replaced 1, updated 0 and skipped 0 records"),
+            (
+                OnConflict::Update(vec![("year".to_string(), IntermediateValue::Numeric(2010f64))]),
+                "Inserted 0, replaced 0, updated 1 and skipped 0 records"
+            ),
+        ];
+
+        for (on_conflict, expected) in cases {
+            let mut cmd = InsertCommand::new(
+                &mut db,
+                "library".to_string(),
+                record.clone(),
+                on_conflict,
+                None
+            );
+
+            let result = cmd.execute().unwrap();
+            assert_eq!(result.to_string().trim_end(), expected);
+        }
+
+        let mut cmd = InsertCommand::new(
+            &mut db,
+            "library".to_string(),
+            record,
+            OnConflict::Fail,
+            None
+        );
+        assert!(matches!(cmd.execute(), Err(Error::AlreadyExistsError(_))));
+
+        let table = db.get_table(&"library".to_string()).unwrap();
+        assert_eq!(table.len(), 1);
+        assert!(matches!(table.to_slice().records[0].values.get("year"), Some(Value::Int(2010))));
+    }
 }
@@ -0,0 +1,99 @@
+﻿use crate::commands::clauses::clause::AnyClause;
+use crate::commands::command::{Command, ExecutionSuccessValue};
+use crate::commands::select::SelectCommand;
+use crate::database::databases::Database;
+use crate::database::key::DatabaseKey;
+use crate::database::record::Projection;
+use crate::database::slice::SelectResult;
+use crate::database::table::OnConflict;
+use crate::errors;
+
+pub struct InsertSelectCommand<'a, K: DatabaseKey> {
+    database: &'a mut Database<K>,
+    table: String,
+    source: String,
+    fields: Vec<Projection>,
+    clauses: Vec<AnyClause>,
+    on_conflict: OnConflict,
+    returning: Option<Vec<String>>,
+}
+
+impl<'a, K: DatabaseKey> InsertSelectCommand<'a, K> {
+    pub fn new(database: &'a mut Database<K>, table: String, source: String, fields: Vec<Projection>, clauses: Vec<AnyClause>, on_conflict: OnConflict, returning: Option<Vec<String>>) -> Self {
+        Self { database, table, source, fields, clauses, on_conflict, returning }
+    }
+}
+
+impl<K: DatabaseKey> Command for InsertSelectCommand<'_, K> {
+    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
+        let source = self.database.get_source(&self.source)?;
+        let result = SelectCommand::run(&source, &self.fields, &self.clauses)?;
+
+        let records = result.rows
+            .iter()
+            .map(|row| row.to_intermediate_record())
+            .collect::<Vec<_>>();
+
+        if let Some(returning) = &self.returning {
+            self.database.get_table(&self.table)?.validate_fields(returning)?;
+        }
+
+        let (summary, written) = self.database.insert_records(&self.table, records, &self.on_conflict)?;
+
+        match &self.returning {
+            Some(returning) => Ok(ExecutionSuccessValue::SelectResult(SelectResult::from_fields(&written, returning)?)),
+            None => Ok(ExecutionSuccessValue::Inserted(summary)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::clauses::evaluable::{AnyEvaluable, CompOp, Comparison};
+    use crate::commands::clauses::r#where::WhereClause;
+    use crate::commands::create::CreateCommand;
+    use crate::commands::fixtures::create_books;
+    use crate::database::key::AnyKey;
+    use crate::database::table::InsertSummary;
+    use crate::database::types::FieldType;
+    use crate::database::value::IntermediateValue;
+    use super::*;
+
+    fn create_table(db: &mut Database<AnyKey>, name: &str) {
+        let mut cmd = CreateCommand::new(
+            db,
+            name.to_string(),
+            vec!["id".to_string()],
+            vec![
+                ("id".to_string(), FieldType::Int),
+                ("year".to_string(), FieldType::Int)
+            ]
+        );
+        cmd.execute().unwrap();
+    }
+
+    #[test]
+    fn test_insert_select_command() {
+        let mut db = Database::<AnyKey>::new();
+        create_books(&mut db, Vec::new());
+        create_table(&mut db, "archive");
+
+        let mut cmd = InsertSelectCommand::new(
+            &mut db,
+            "archive".to_string(),
+            "books".to_string(),
+            vec!["id".into(), "year".into()],
+            vec![AnyClause::Where(WhereClause::new(AnyEvaluable::Comp(
+                Comparison::new("year".to_string(), CompOp::Lt, IntermediateValue::Numeric(1990f64))
+            )))],
+            OnConflict::Fail,
+            None
+        );
+
+        let result = cmd.execute().unwrap();
+
+        assert!(matches!(result, ExecutionSuccessValue::Inserted(InsertSummary { inserted: 2, .. })));
+        assert_eq!(db.get_table(&"archive".to_string()).unwrap().len(), 2);
+        assert_eq!(db.get_table(&"books".to_string()).unwrap().len(), 3);
+    }
+}
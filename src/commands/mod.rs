@@ -1,8 +1,18 @@
 ﻿pub mod command;
 pub mod create;
+pub mod create_select;
 pub mod insert;
+pub mod insert_select;
 pub mod delete;
+pub mod describe;
+pub mod index;
+pub mod explain;
 pub mod select;
 pub mod save;
 pub mod read;
+pub mod show;
+pub mod statement;
+pub mod view;
 pub mod clauses;
+#[cfg(test)]
+pub mod fixtures;
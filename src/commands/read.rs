@@ -4,6 +4,7 @@ use crate::database::databases::{Database};
 use crate::database::key::DatabaseKey;
 use crate::errors;
 use crate::errors::Error;
+use crate::parser::is_complete_command;
 
 pub struct ReadCommand<'a, K: DatabaseKey> {
     pub database: &'a mut Database<K>,
@@ -35,7 +36,7 @@ impl<'a, K: DatabaseKey> Command for ReadCommand<'a, K> {
             let trimmed = line.trim();
             if trimmed.is_empty() { continue; }
 
-            let command_str = if trimmed.starts_with("CREATE") {
+            let command_str = if trimmed.starts_with("CREATE") && !is_complete_command(trimmed) {
                 match lines.next() {
                     Some(next_line) => format!("{}\n{}", trimmed, next_line.trim()),
                     None => { return Err(Error::IOError("CREATE command requires FIELDS after a newline".to_string())); },
@@ -52,6 +53,7 @@ impl<'a, K: DatabaseKey> Command for ReadCommand<'a, K> {
 
 #[cfg(test)]
 mod tests {
+    use crate::database::key::AnyKey;
     use super::*;
 
     #[test]
@@ -64,7 +66,7 @@ mod tests {
 
         fs::write("read_test_input.txt", script).unwrap();
 
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
         let mut cmd = ReadCommand::new(&mut db, "read_test_input.txt".to_string());
 
         let result = cmd.execute().unwrap();
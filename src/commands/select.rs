@@ -1,37 +1,39 @@
 ﻿use crate::commands::clauses::clause::{AnyClause, Clause};
 use crate::commands::command::{Command, ExecutionSuccessValue};
 use crate::database::key::DatabaseKey;
+use crate::database::record::Projection;
 use crate::database::slice::SelectResult;
-use crate::database::table::Table;
+use crate::database::source::SelectSource;
 use crate::errors;
+use crate::errors::Error;
 
 pub struct SelectCommand<'a, K: DatabaseKey> {
-    table: &'a Table<K>,
-    fields: Vec<String>,
+    source: SelectSource<'a, K>,
+    fields: Vec<Projection>,
     clauses: Vec<AnyClause>,
 }
 
 impl<'a, K: DatabaseKey> SelectCommand<'a, K> {
-    pub fn new(table: &'a Table<K>, fields: Vec<String>, clauses: Vec<AnyClause>) -> Self {
-        Self { table, fields, clauses }
+    pub fn new(source: SelectSource<'a, K>, fields: Vec<Projection>, clauses: Vec<AnyClause>) -> Self {
+        Self { source, fields, clauses }
     }
-}
 
-impl<K: DatabaseKey> Command for SelectCommand<'_, K> {
-    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
-        let mut slice = self.table.to_slice();
+    pub fn run(source: &SelectSource<'_, K>, fields: &[Projection], clauses: &[AnyClause]) -> Result<SelectResult, Error> {
+        let mut slice = source.scan(clauses);
 
-        for clause in &self.clauses {
+        for clause in clauses {
             slice = clause.apply(slice)?;
         }
 
-        let mut result_rows = Vec::new();
-        for record in slice.records {
-            let projected = record.project_fields(&self.fields)?;
-            result_rows.push(projected);
-        }
+        SelectResult::from_records(&slice.records, fields)
+    }
+}
 
-        Ok(ExecutionSuccessValue::SelectResult(SelectResult { rows: result_rows }))
+impl<K: DatabaseKey> Command for SelectCommand<'_, K> {
+    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
+        let result = Self::run(&self.source, &self.fields, &self.clauses)?;
+
+        Ok(ExecutionSuccessValue::SelectResult(result))
     }
 }
 
@@ -44,15 +46,17 @@ mod tests {
     use crate::commands::create::CreateCommand;
     use crate::commands::insert::InsertCommand;
     use crate::database::databases::Database;
+    use crate::database::key::AnyKey;
+    use crate::database::table::OnConflict;
     use crate::database::types::FieldType;
     use crate::database::value::{IntermediateValue, Value};
     use super::*;
 
-    fn prepare_db(mut db: &mut Database::<String>) {
+    fn prepare_db(db: &mut Database::<AnyKey>) {
         let mut cmd = CreateCommand::new(
-            &mut db,
+            db,
             "library".to_string(),
-            "id".to_string(),
+            vec!["id".to_string()],
             vec![
                 ("id".to_string(), FieldType::String),
                 ("year".to_string(), FieldType::Int)
@@ -61,41 +65,50 @@ mod tests {
         cmd.execute().unwrap();
 
         let mut cmd = InsertCommand::new(
-            db.get_table(&"library".to_string()).unwrap(),
+            db,
+            "library".to_string(),
             vec![
                 ("id".to_string(), IntermediateValue::String("1".to_string())),
                 ("year".to_string(), IntermediateValue::Numeric(2002f64)),
-            ]
+            ],
+            OnConflict::Fail,
+            None
         );
         cmd.execute().unwrap();
 
         let mut cmd = InsertCommand::new(
-            db.get_table(&"library".to_string()).unwrap(),
+            db,
+            "library".to_string(),
             vec![
                 ("id".to_string(), IntermediateValue::String("2".to_string())),
                 ("year".to_string(), IntermediateValue::Numeric(2001f64)),
-            ]
+            ],
+            OnConflict::Fail,
+            None
         );
         cmd.execute().unwrap();
 
         let mut cmd = InsertCommand::new(
-            db.get_table(&"library".to_string()).unwrap(),
+            db,
+            "library".to_string(),
             vec![
                 ("id".to_string(), IntermediateValue::String("3".to_string())),
                 ("year".to_string(), IntermediateValue::Numeric(2000f64)),
-            ]
+            ],
+            OnConflict::Fail,
+            None
         );
         cmd.execute().unwrap();
     }
 
     #[test]
     fn test_select_command() {
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
         prepare_db(&mut db);
 
         let mut cmd = SelectCommand::new(
-            db.get_table(&"library".to_string()).unwrap(),
-            vec!["id".to_string(), "year".to_string()],
+            db.get_source(&"library".to_string()).unwrap(),
+            vec!["id".into(), "year".into()],
             vec![]
         );
 
@@ -110,12 +123,12 @@ mod tests {
     
     #[test]
     fn test_select_where() {
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
         prepare_db(&mut db);
 
         let mut cmd = SelectCommand::new(
-            db.get_table(&"library".to_string()).unwrap(),
-            vec!["id".to_string(), "year".to_string()],
+            db.get_source(&"library".to_string()).unwrap(),
+            vec!["id".into(), "year".into()],
             vec![AnyClause::Where(WhereClause::new(
                 AnyEvaluable::Or(
                     ComparisonOr::new(
@@ -141,12 +154,12 @@ mod tests {
 
     #[test]
     fn test_select_order() {
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
         prepare_db(&mut db);
 
         let mut cmd = SelectCommand::new(
-            db.get_table(&"library".to_string()).unwrap(),
-            vec!["id".to_string(), "year".to_string()],
+            db.get_source(&"library".to_string()).unwrap(),
+            vec!["id".into(), "year".into()],
             vec![AnyClause::Order(OrderByClause::new(vec!["year".to_string()]))]
         );
 
@@ -170,12 +183,12 @@ mod tests {
 
     #[test]
     fn test_select_limit() {
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
         prepare_db(&mut db);
 
         let mut cmd = SelectCommand::new(
-            db.get_table(&"library".to_string()).unwrap(),
-            vec!["id".to_string(), "year".to_string()],
+            db.get_source(&"library".to_string()).unwrap(),
+            vec!["id".into(), "year".into()],
             vec![AnyClause::Limit(LimitClause::new(1))]
         );
 
@@ -0,0 +1,74 @@
+﻿use crate::commands::command::{Command, ExecutionSuccessValue};
+use crate::commands::describe::describe_fields;
+use crate::database::catalog::{catalog_records, SYS_TABLES};
+use crate::database::databases::Database;
+use crate::database::ident::quote_ident;
+use crate::database::key::DatabaseKey;
+use crate::database::slice::SelectResult;
+use crate::database::value::Value;
+use crate::errors;
+
+pub struct ShowTablesCommand<'a, K: DatabaseKey> {
+    database: &'a Database<K>,
+}
+
+impl<'a, K: DatabaseKey> ShowTablesCommand<'a, K> {
+    pub fn new(database: &'a Database<K>) -> Self {
+        Self { database }
+    }
+}
+
+impl<K: DatabaseKey> Command for ShowTablesCommand<'_, K> {
+    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
+        let mut records = catalog_records(self.database, SYS_TABLES).unwrap_or_default();
+
+        for record in &mut records {
+            let Some(Value::String(name)) = record.values.get("name") else { continue };
+
+            let fields = describe_fields(self.database, name)?
+                .into_iter()
+                .map(|(field, field_type, _)| format!("{}: {}", quote_ident(&field), field_type))
+                .collect::<Vec<_>>()
+                .join(", ");
+            record.values.insert("fields".to_string(), Value::String(fields));
+        }
+
+        let fields = ["name", "key", "fields", "rows", "kind"].map(String::from);
+
+        Ok(ExecutionSuccessValue::SelectResult(SelectResult::from_fields(&records, &fields)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::create::CreateCommand;
+    use crate::database::key::AnyKey;
+    use crate::database::types::FieldType;
+    use super::*;
+
+    #[test]
+    fn test_show_tables_command() {
+        let mut db = Database::<AnyKey>::new();
+
+        for name in ["cars", "books"] {
+            let mut cmd = CreateCommand::new(
+                &mut db,
+                name.to_string(),
+                vec!["id".to_string()],
+                vec![
+                    ("id".to_string(), FieldType::Int),
+                    ("year".to_string(), FieldType::Int)
+                ]
+            );
+            cmd.execute().unwrap();
+        }
+
+        let mut cmd = ShowTablesCommand::new(&db);
+
+        let result = cmd.execute().unwrap();
+
+        assert_eq!(result.to_string(), "name: \"books\", key: \"id\", fields: \"id: Int, year: Int\", rows: 0, kind: \"table\"
+name: \"cars\", key: \"id\", fields: \"id: Int, year: Int\", rows: 0, kind: \"table\"
+");
+    }
+}
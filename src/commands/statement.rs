@@ -0,0 +1,255 @@
+﻿use crate::commands::clauses::clause::AnyClause;
+use crate::commands::command::AnyCommand;
+use crate::commands::create::CreateCommand;
+use crate::commands::create_select::CreateSelectCommand;
+use crate::commands::delete::DeleteCommand;
+use crate::commands::describe::DescribeCommand;
+use crate::commands::explain::ExplainCommand;
+use crate::commands::index::CreateIndexCommand;
+use crate::commands::insert::InsertCommand;
+use crate::commands::insert_select::InsertSelectCommand;
+use crate::commands::read::ReadCommand;
+use crate::commands::save::SaveCommand;
+use crate::commands::select::SelectCommand;
+use crate::commands::show::ShowTablesCommand;
+use crate::commands::view::{CreateMaterializedViewCommand, CreateViewCommand, DropViewCommand, RefreshCommand};
+use crate::database::constraint::Constraint;
+use crate::database::databases::Database;
+use crate::database::key::DatabaseKey;
+use crate::database::record::Projection;
+use crate::database::table::OnConflict;
+use crate::database::temporal::CurrentTime;
+use crate::database::types::FieldType;
+use crate::database::value::{IntermediateValue, KeyValue};
+use crate::errors::Error;
+
+#[derive(Clone, Debug)]
+pub struct SelectStatement {
+    pub table: String,
+    pub fields: Vec<Projection>,
+    pub clauses: Vec<AnyClause>,
+}
+
+impl SelectStatement {
+    pub fn values_mut(&mut self) -> Vec<&mut IntermediateValue> {
+        self.clauses.iter_mut().flat_map(|clause| clause.values_mut()).collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum StatementKey {
+    Literal(KeyValue),
+    Bound(IntermediateValue),
+}
+
+#[derive(Clone, Debug)]
+pub enum Statement {
+    Create { name: String, key: Vec<String>, auto: bool, fields: Vec<(String, FieldType)>, constraints: Vec<Constraint>, nullable: Vec<String> },
+    CreateSelect { name: String, key: Vec<String>, select: SelectStatement },
+    CreateView { name: String, select: SelectStatement },
+    CreateIndex { table: String, field: String },
+    CreateMaterializedView { name: String, key: Option<Vec<String>>, select: SelectStatement },
+    Delete { table: String, key: Vec<StatementKey>, returning: Option<Vec<String>> },
+    Describe { table: String },
+    DropView { name: String },
+    Explain { select: SelectStatement, analyze: bool },
+    Insert { table: String, assignments: Vec<(String, IntermediateValue)>, on_conflict: OnConflict, returning: Option<Vec<String>> },
+    InsertSelect { table: String, select: SelectStatement, on_conflict: OnConflict, returning: Option<Vec<String>> },
+    Read { path: String },
+    Refresh { name: String },
+    Save { path: String },
+    Select(SelectStatement),
+    ShowTables,
+}
+
+impl Statement {
+    pub fn values_mut(&mut self) -> Vec<&mut IntermediateValue> {
+        match self {
+            Statement::CreateSelect { select, .. } | Statement::CreateView { select, .. } | Statement::CreateMaterializedView { select, .. } | Statement::Explain { select, .. } | Statement::Select(select) => select.values_mut(),
+            Statement::Delete { key, .. } => key
+                .iter_mut()
+                .filter_map(|part| match part {
+                    StatementKey::Bound(value) => Some(value),
+                    StatementKey::Literal(_) => None,
+                })
+                .collect(),
+            Statement::Insert { assignments, on_conflict, .. } => {
+                let mut values = assignments.iter_mut().map(|(_, value)| value).collect::<Vec<_>>();
+                values.extend(on_conflict_values_mut(on_conflict));
+                values
+            },
+            Statement::InsertSelect { select, on_conflict, .. } => {
+                let mut values = select.values_mut();
+                values.extend(on_conflict_values_mut(on_conflict));
+                values
+            },
+            Statement::Create { constraints, .. } => constraints.iter_mut().flat_map(|constraint| constraint.values_mut()).collect(),
+            Statement::CreateIndex { .. } | Statement::Describe { .. } | Statement::DropView { .. } | Statement::Read { .. } | Statement::Refresh { .. }
+            | Statement::Save { .. } | Statement::ShowTables => Vec::new(),
+        }
+    }
+
+    pub fn parameter_count(&mut self) -> usize {
+        self.values_mut()
+            .into_iter()
+            .filter_map(|value| match value {
+                IntermediateValue::Parameter(n) => Some(*n),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn bind(&mut self, params: &[IntermediateValue]) -> Result<(), Error> {
+        if params.iter().any(|param| matches!(param, IntermediateValue::Parameter(_))) {
+            return Err(Error::TypeError("Parameters cannot be bound to other parameters".to_string()));
+        }
+
+        let expected = self.parameter_count();
+        if params.len() != expected {
+            return Err(Error::NotSpecifiedError(format!("Expected {} parameters but got {}", expected, params.len())));
+        }
+
+        for value in self.values_mut() {
+            if let IntermediateValue::Parameter(n) = value {
+                *value = params[*n - 1].clone();
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn resolve_current_time(&mut self, now: i64) -> Result<(), Error> {
+        for value in self.values_mut() {
+            value.resolve_current_time(now)?;
+        }
+
+        Ok(())
+    }
+
+    pub fn into_command<K: DatabaseKey>(self, database: &mut Database<K>) -> Result<AnyCommand<'_, K>, Error> {
+        let command = match self {
+            Statement::Create { name, key, auto, fields, constraints, nullable } =>
+                AnyCommand::Create(CreateCommand::new(database, name, key, fields).with_options(auto, constraints).with_nullable(nullable)),
+            Statement::CreateSelect { name, key, select } =>
+                AnyCommand::CreateSelect(CreateSelectCommand::new(database, name, key, select.table, select.fields, select.clauses)),
+            Statement::CreateView { name, select } =>
+                AnyCommand::CreateView(CreateViewCommand::new(database, name, select)),
+            Statement::CreateIndex { table, field } =>
+                AnyCommand::CreateIndex(CreateIndexCommand::new(database, table, field)),
+            Statement::CreateMaterializedView { name, key, select } =>
+                AnyCommand::CreateMaterializedView(CreateMaterializedViewCommand::new(database, name, key, select)),
+            Statement::Delete { table, key, returning } => {
+                let key = key
+                    .into_iter()
+                    .map(|part| match part {
+                        StatementKey::Literal(key) => Ok(key),
+                        StatementKey::Bound(value) => KeyValue::try_from(&value),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                AnyCommand::Delete(DeleteCommand::new(database, table, key, returning))
+            },
+            Statement::Describe { table } =>
+                AnyCommand::Describe(DescribeCommand::new(database, table)),
+            Statement::DropView { name } =>
+                AnyCommand::DropView(DropViewCommand::new(database, name)),
+            Statement::Explain { select, analyze } =>
+                AnyCommand::Explain(ExplainCommand::new(database.get_source(&select.table)?, select.table, select.fields, select.clauses, analyze)),
+            Statement::Insert { table, assignments, on_conflict, returning } =>
+                AnyCommand::Insert(InsertCommand::new(database, table, assignments, on_conflict, returning)),
+            Statement::InsertSelect { table, select, on_conflict, returning } =>
+                AnyCommand::InsertSelect(InsertSelectCommand::new(database, table, select.table, select.fields, select.clauses, on_conflict, returning)),
+            Statement::Read { path } =>
+                AnyCommand::Read(ReadCommand::new(database, path)),
+            Statement::Refresh { name } =>
+                AnyCommand::Refresh(RefreshCommand::new(database, name)),
+            Statement::Save { path } =>
+                AnyCommand::Save(SaveCommand::new(path, database.get_session_commands())),
+            Statement::Select(select) =>
+                AnyCommand::Select(SelectCommand::new(database.get_source(&select.table)?, select.fields, select.clauses)),
+            Statement::ShowTables =>
+                AnyCommand::ShowTables(ShowTablesCommand::new(database)),
+        };
+
+        Ok(command)
+    }
+}
+
+fn on_conflict_values_mut(on_conflict: &mut OnConflict) -> Vec<&mut IntermediateValue> {
+    match on_conflict {
+        OnConflict::Update(assignments) => assignments.iter_mut().map(|(_, value)| value).collect(),
+        OnConflict::Fail | OnConflict::Ignore | OnConflict::Replace => Vec::new(),
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ParameterSpan {
+    pub start: usize,
+    pub end: usize,
+    pub index: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct CurrentTimeSpan {
+    pub start: usize,
+    pub end: usize,
+    pub current: CurrentTime,
+}
+
+#[derive(Clone, Debug)]
+pub struct PreparedStatement {
+    source: String,
+    statement: Statement,
+    parameters: Vec<ParameterSpan>,
+    current_times: Vec<CurrentTimeSpan>,
+}
+
+impl PreparedStatement {
+    pub fn new(source: String, statement: Statement, parameters: Vec<ParameterSpan>, current_times: Vec<CurrentTimeSpan>) -> Self {
+        Self { source, statement, parameters, current_times }
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub fn parameters(&self) -> &[ParameterSpan] {
+        &self.parameters
+    }
+
+    pub fn bind(&self, params: &[IntermediateValue], now: i64) -> Result<Statement, Error> {
+        let mut statement = self.statement.clone();
+        statement.bind(params)?;
+        statement.resolve_current_time(now)?;
+        Ok(statement)
+    }
+
+    pub fn render(&self, params: &[IntermediateValue], now: i64) -> Result<String, Error> {
+        let mut replacements = Vec::new();
+
+        for parameter in &self.parameters {
+            let param = params.get(parameter.index - 1)
+                .ok_or_else(|| Error::NotSpecifiedError(format!("Parameter ${} is not bound", parameter.index)))?;
+            replacements.push((parameter.start, parameter.end, param.to_string()));
+        }
+
+        for current_time in &self.current_times {
+            replacements.push((current_time.start, current_time.end, current_time.current.resolve(now)?.to_string()));
+        }
+
+        replacements.sort_by_key(|(start, _, _)| *start);
+
+        let mut rendered = String::new();
+        let mut last = 0;
+
+        for (start, end, replacement) in replacements {
+            rendered.push_str(&self.source[last..start]);
+            rendered.push_str(&replacement);
+            last = end;
+        }
+
+        rendered.push_str(&self.source[last..]);
+
+        Ok(rendered)
+    }
+}
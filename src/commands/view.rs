@@ -0,0 +1,165 @@
+﻿use crate::commands::command::{Command, ExecutionSuccessValue};
+use crate::commands::select::SelectCommand;
+use crate::commands::statement::SelectStatement;
+use crate::database::databases::Database;
+use crate::database::key::DatabaseKey;
+use crate::database::view::MaterializedView;
+use crate::errors::{self, Error};
+
+pub struct CreateViewCommand<'a, K: DatabaseKey> {
+    database: &'a mut Database<K>,
+    name: String,
+    select: SelectStatement,
+}
+
+impl<'a, K: DatabaseKey> CreateViewCommand<'a, K> {
+    pub fn new(database: &'a mut Database<K>, name: String, select: SelectStatement) -> Self {
+        Self { database, name, select }
+    }
+}
+
+impl<K: DatabaseKey> Command for CreateViewCommand<'_, K> {
+    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
+        let source = self.database.get_source(&self.select.table)?;
+        SelectCommand::run(&source, &self.select.fields, &self.select.clauses)?;
+
+        self.database.add_view(self.name.clone(), self.select.clone())?;
+
+        Ok(ExecutionSuccessValue::Success(format!("View '{}' created successfully", self.name)))
+    }
+}
+
+pub struct DropViewCommand<'a, K: DatabaseKey> {
+    database: &'a mut Database<K>,
+    name: String,
+}
+
+impl<'a, K: DatabaseKey> DropViewCommand<'a, K> {
+    pub fn new(database: &'a mut Database<K>, name: String) -> Self {
+        Self { database, name }
+    }
+}
+
+impl<K: DatabaseKey> Command for DropViewCommand<'_, K> {
+    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
+        self.database.drop_view(&self.name)?;
+
+        Ok(ExecutionSuccessValue::Success(format!("View '{}' dropped successfully", self.name)))
+    }
+}
+
+pub struct CreateMaterializedViewCommand<'a, K: DatabaseKey> {
+    database: &'a mut Database<K>,
+    name: String,
+    key: Option<Vec<String>>,
+    select: SelectStatement,
+}
+
+impl<'a, K: DatabaseKey> CreateMaterializedViewCommand<'a, K> {
+    pub fn new(database: &'a mut Database<K>, name: String, key: Option<Vec<String>>, select: SelectStatement) -> Self {
+        Self { database, name, key, select }
+    }
+}
+
+impl<K: DatabaseKey> Command for CreateMaterializedViewCommand<'_, K> {
+    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
+        let key = match &self.key {
+            Some(key) => key.clone(),
+            None => self.database.tables()
+                .get(&self.select.table)
+                .map(|table| table.key().clone())
+                .ok_or_else(|| Error::NotSpecifiedError(format!("Materialized view '{}' needs a KEY", self.name)))?,
+        };
+
+        let records = self.database.add_materialized_view(self.name.clone(), MaterializedView::new(key, self.select.clone()))?;
+
+        Ok(ExecutionSuccessValue::Success(format!("Materialized view '{}' created with {} records", self.name, records)))
+    }
+}
+
+pub struct RefreshCommand<'a, K: DatabaseKey> {
+    database: &'a mut Database<K>,
+    name: String,
+}
+
+impl<'a, K: DatabaseKey> RefreshCommand<'a, K> {
+    pub fn new(database: &'a mut Database<K>, name: String) -> Self {
+        Self { database, name }
+    }
+}
+
+impl<K: DatabaseKey> Command for RefreshCommand<'_, K> {
+    fn execute(&mut self) -> Result<ExecutionSuccessValue, errors::Error> {
+        let records = self.database.refresh_view(&self.name)?;
+
+        Ok(ExecutionSuccessValue::Success(format!("Materialized view '{}' refreshed with {} records", self.name, records)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::clauses::clause::AnyClause;
+    use crate::commands::clauses::evaluable::{AnyEvaluable, CompOp, Comparison};
+    use crate::commands::clauses::r#where::WhereClause;
+    use crate::commands::fixtures::create_books;
+    use crate::database::key::{AnyKey, TableKey};
+    use crate::database::value::IntermediateValue;
+    use super::*;
+
+    fn old_books() -> SelectStatement {
+        SelectStatement {
+            table: "books".to_string(),
+            fields: vec!["id".into()],
+            clauses: vec![AnyClause::Where(WhereClause::new(AnyEvaluable::Comp(
+                Comparison::new("year".to_string(), CompOp::Lt, IntermediateValue::Numeric(1990f64))
+            )))],
+        }
+    }
+
+    #[test]
+    fn test_create_and_drop_view_command() {
+        let mut db = Database::<AnyKey>::new();
+        create_books(&mut db, Vec::new());
+
+        let mut cmd = CreateViewCommand::new(&mut db, "old_books".to_string(), old_books());
+        cmd.execute().unwrap();
+
+        let source = db.get_source(&"old_books".to_string()).unwrap();
+        assert_eq!(source.len(), 2);
+        assert_eq!(source.fields().len(), 1);
+
+        let mut cmd = CreateViewCommand::new(&mut db, "books".to_string(), old_books());
+        assert!(matches!(cmd.execute(), Err(Error::AlreadyExistsError(_))));
+
+        let mut cmd = DropViewCommand::new(&mut db, "old_books".to_string());
+        cmd.execute().unwrap();
+
+        assert!(matches!(db.get_source(&"old_books".to_string()), Err(Error::NotExistError(_))));
+
+        let mut cmd = DropViewCommand::new(&mut db, "old_books".to_string());
+        assert!(matches!(cmd.execute(), Err(Error::NotExistError(_))));
+    }
+
+    #[test]
+    fn test_materialized_view_command() {
+        let mut db = Database::<AnyKey>::new();
+        create_books(&mut db, Vec::new());
+
+        let mut cmd = CreateMaterializedViewCommand::new(&mut db, "old_books".to_string(), None, old_books());
+        cmd.execute().unwrap();
+
+        assert_eq!(db.tables().get("old_books").unwrap().len(), 2);
+        assert!(matches!(db.get_table(&"old_books".to_string()), Err(Error::TypeError(_))));
+
+        db.get_table(&"books".to_string()).unwrap().delete_record(&TableKey(vec![AnyKey::Int(1)])).unwrap();
+        assert_eq!(db.tables().get("old_books").unwrap().len(), 2);
+
+        let mut cmd = RefreshCommand::new(&mut db, "old_books".to_string());
+        cmd.execute().unwrap();
+        assert_eq!(db.tables().get("old_books").unwrap().len(), 1);
+
+        let mut cmd = DropViewCommand::new(&mut db, "old_books".to_string());
+        cmd.execute().unwrap();
+        assert!(!db.has_table(&"old_books".to_string()));
+    }
+}
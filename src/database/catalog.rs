@@ -0,0 +1,146 @@
+﻿use std::collections::HashMap;
+use crate::database::databases::Database;
+use crate::database::key::DatabaseKey;
+use crate::database::record::Record;
+use crate::database::types::FieldType;
+use crate::database::value::Value;
+
+pub const SYS_TABLES: &str = "sys_tables";
+pub const SYS_FIELDS: &str = "sys_fields";
+
+pub fn is_catalog_name(name: &str) -> bool {
+    name == SYS_TABLES || name == SYS_FIELDS
+}
+
+pub fn catalog_fields(name: &str) -> Option<HashMap<String, FieldType>> {
+    let fields = match name {
+        SYS_TABLES => vec![
+            ("name", FieldType::String),
+            ("key", FieldType::String),
+            ("fields", FieldType::Int),
+            ("rows", FieldType::Int),
+            ("kind", FieldType::String),
+        ],
+        SYS_FIELDS => vec![
+            ("table_name", FieldType::String),
+            ("field", FieldType::String),
+            ("type", FieldType::String),
+            ("key", FieldType::Bool),
+            ("nullable", FieldType::Bool),
+        ],
+        _ => return None,
+    };
+
+    Some(fields.into_iter().map(|(field, field_type)| (field.to_string(), field_type)).collect())
+}
+
+pub fn catalog_records<K: DatabaseKey>(database: &Database<K>, name: &str) -> Option<Vec<Record>> {
+    match name {
+        SYS_TABLES => Some(sys_tables(database)),
+        SYS_FIELDS => Some(sys_fields(database)),
+        _ => None,
+    }
+}
+
+fn record(values: Vec<(&str, Value)>) -> Record {
+    Record { values: values.into_iter().map(|(field, value)| (field.to_string(), value)).collect() }
+}
+
+// Views store no rows, so they report 0 instead of being run for every catalog read.
+fn sys_tables<K: DatabaseKey>(database: &Database<K>) -> Vec<Record> {
+    let tables = database.tables()
+        .iter()
+        .map(|(name, table)| {
+            let kind = match database.materialized_views().contains_key(name) {
+                true => "materialized view",
+                false => "table",
+            };
+
+            (name, table.key_name(), table.fields().len(), table.len(), kind)
+        });
+
+    let views = database.views()
+        .keys()
+        .map(|name| (name, String::new(), database.source_fields(name).map(|fields| fields.len()).unwrap_or(0), 0, "view"));
+
+    let mut relations = tables.chain(views).collect::<Vec<_>>();
+    relations.sort_by_key(|(name, ..)| *name);
+
+    relations
+        .into_iter()
+        .map(|(name, key, fields, rows, kind)| record(vec![
+            ("name", Value::String(name.clone())),
+            ("key", Value::String(key)),
+            ("fields", Value::Int(fields as i64)),
+            ("rows", Value::Int(rows as i64)),
+            ("kind", Value::String(kind.to_string())),
+        ]))
+        .collect()
+}
+
+fn sys_fields<K: DatabaseKey>(database: &Database<K>) -> Vec<Record> {
+    let tables = database.tables()
+        .iter()
+        .flat_map(|(name, table)| table.fields()
+            .iter()
+            .map(move |(field, field_type)| (name, field.clone(), field_type.clone(), table.is_key(field), table.is_nullable(field))));
+
+    let views = database.views()
+        .keys()
+        .flat_map(|name| database.source_fields(name)
+            .unwrap_or_default()
+            .into_iter()
+            .map(move |(field, field_type)| {
+                let nullable = database.is_source_field_nullable(name, &field);
+                (name, field, field_type, false, nullable)
+            }));
+
+    let mut fields = tables.chain(views).collect::<Vec<_>>();
+    fields.sort_by(|(name, field, ..), (other_name, other_field, ..)| (name, field).cmp(&(other_name, other_field)));
+
+    fields
+        .into_iter()
+        .map(|(name, field, field_type, key, nullable)| record(vec![
+            ("table_name", Value::String(name.clone())),
+            ("field", Value::String(field)),
+            ("type", Value::String(field_type.to_string())),
+            ("key", Value::Bool(key)),
+            ("nullable", Value::Bool(nullable)),
+        ]))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::commands::command::Command;
+    use crate::commands::create::CreateCommand;
+    use crate::database::key::AnyKey;
+    use super::*;
+
+    #[test]
+    fn test_catalog_records() {
+        let mut db = Database::<AnyKey>::new();
+
+        let mut cmd = CreateCommand::new(
+            &mut db,
+            "books".to_string(),
+            vec!["id".to_string()],
+            vec![
+                ("id".to_string(), FieldType::Int),
+                ("title".to_string(), FieldType::String)
+            ]
+        );
+        cmd.execute().unwrap();
+
+        let tables = catalog_records(&db, SYS_TABLES).unwrap();
+        assert_eq!(tables.len(), 1);
+        assert!(matches!(tables[0].values.get("fields"), Some(Value::Int(2))));
+
+        let fields = catalog_records(&db, SYS_FIELDS).unwrap();
+        assert_eq!(fields.len(), 2);
+        assert!(matches!(fields[0].values.get("key"), Some(Value::Bool(true))));
+        assert!(matches!(fields[1].values.get("type"), Some(Value::String(t)) if t == "String"));
+
+        assert!(catalog_records(&db, "sys_missing").is_none());
+    }
+}
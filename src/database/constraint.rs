@@ -0,0 +1,57 @@
+﻿use std::fmt::{Display, Formatter};
+use crate::commands::clauses::evaluable::AnyEvaluable;
+use crate::database::ident::quote_ident;
+use crate::database::value::IntermediateValue;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OnDelete {
+    Restrict,
+    Cascade,
+    SetNull,
+}
+
+impl Display for OnDelete {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OnDelete::Restrict => write!(f, "RESTRICT"),
+            OnDelete::Cascade => write!(f, "CASCADE"),
+            OnDelete::SetNull => write!(f, "SET NULL"),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Constraint {
+    Unique { field: String },
+    Check { name: Option<String>, condition: AnyEvaluable },
+    References { field: String, table: String, on_delete: OnDelete },
+}
+
+impl Constraint {
+    pub fn fields(&self) -> Vec<&String> {
+        match self {
+            Constraint::Unique { field } => vec![field],
+            Constraint::Check { condition, .. } => condition.fields(),
+            Constraint::References { field, .. } => vec![field],
+        }
+    }
+
+    pub fn values_mut(&mut self) -> Vec<&mut IntermediateValue> {
+        match self {
+            Constraint::Check { condition, .. } => condition.values_mut(),
+            Constraint::Unique { .. } | Constraint::References { .. } => Vec::new(),
+        }
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Constraint::Unique { field } => write!(f, "UNIQUE ({})", quote_ident(field)),
+            Constraint::Check { name: Some(name), .. } => write!(f, "{}", quote_ident(name)),
+            Constraint::Check { name: None, condition } => write!(f, "CHECK ({})", condition),
+            Constraint::References { field, table, on_delete } =>
+                write!(f, "FOREIGN KEY ({}) REFERENCES {} ON DELETE {}", quote_ident(field), quote_ident(table), on_delete),
+        }
+    }
+}
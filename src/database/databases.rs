@@ -1,49 +1,350 @@
-﻿use std::collections::HashMap;
+﻿use std::collections::{HashMap, HashSet};
 use crate::commands::command::{Command, ExecutionSuccessValue};
-use crate::database::key::DatabaseKey;
-use crate::database::table::Table;
-use crate::database::types::{FieldType, KeyType};
+use crate::commands::create_select::CreateSelectCommand;
+use crate::commands::select::SelectCommand;
+use crate::commands::statement::{PreparedStatement, SelectStatement, Statement};
+use crate::database::catalog::{catalog_fields, catalog_records, is_catalog_name};
+use crate::database::constraint::{Constraint, OnDelete};
+use crate::database::key::{AnyKey, DatabaseKey, TableKey};
+use crate::database::record::{IntermediateRecord, Record};
+use crate::database::slice::TableSlice;
+use crate::database::source::SelectSource;
+use crate::database::table::{InsertSummary, OnConflict, Table};
+use crate::database::temporal::now;
+use crate::database::types::FieldType;
+use crate::database::view::MaterializedView;
 use crate::errors::Error;
-use crate::parser::parse_command;
+use crate::database::value::{IntermediateValue, Value};
+use crate::parser::{parse_command, prepare_command, prepare_statement};
 
 #[derive(Clone, Debug)]
 pub struct Database<K: DatabaseKey> {
     tables: HashMap<String, Table<K>>,
+    views: HashMap<String, SelectStatement>,
+    materialized_views: HashMap<String, MaterializedView>,
     session_commands: Vec<String>
 }
 
 impl<K: DatabaseKey> Database<K> {
     pub fn new() -> Self {
-        Self { tables: HashMap::new(), session_commands: Vec::new() }
+        Self { tables: HashMap::new(), views: HashMap::new(), materialized_views: HashMap::new(), session_commands: Vec::new() }
     }
 
     pub fn add_table(&mut self, name: String, table: Table<K>) -> Result<(), Error> {
-        if self.tables.contains_key(&name) {
+        if is_catalog_name(&name) {
+            return Err(Error::AlreadyExistsError(format!("Table name '{}' is reserved for the system catalog", name)));
+        }
+
+        if self.tables.contains_key(&name) || self.views.contains_key(&name) {
             return Err(Error::AlreadyExistsError(format!("Table '{}' already exists", name)));
         }
 
-        if table.key_type()? != K::get_field_type() {
-            return Err(Error::TypeError("Mismatched key type".to_string()));
+        if let Some(key_type) = table.key_types()?.iter().find(|key_type| !K::supports(key_type)) {
+            return Err(Error::TypeError(format!("Fields of type {} cannot be used as a key", key_type)));
+        }
+
+        for constraint in table.constraints() {
+            if let Constraint::References { field, table: target, on_delete } = constraint {
+                if *on_delete == OnDelete::SetNull && !table.is_nullable(field) {
+                    return Err(Error::TypeError(format!("Field '{}' must be declared NULL to use ON DELETE SET NULL", field)));
+                }
+
+                let target_table = match target == &name {
+                    true => &table,
+                    false => self.tables.get(target)
+                        .filter(|_| !self.materialized_views.contains_key(target))
+                        .ok_or_else(|| Error::NotExistError(format!("Referenced table '{}' does not exist", target)))?,
+                };
+
+                if target_table.key_types()? != table.fields().get(field).cloned().into_iter().collect::<Vec<_>>() {
+                    return Err(Error::TypeError(format!("Field '{}' cannot reference '{}' because its key is ({})", field, target, target_table.key_name())));
+                }
+            }
         }
 
         self.tables.insert(name, table);
         Ok(())
     }
 
+    pub fn insert_records(&mut self, name: &String, records: Vec<IntermediateRecord>, on_conflict: &OnConflict) -> Result<(InsertSummary, Vec<Record>), Error> {
+        let staged = self.get_table(name)?.stage_records(records, on_conflict)?;
+        self.check_references(name, &staged.records)?;
+
+        Ok(self.get_table(name)?.commit_records(staged))
+    }
+
+    fn check_references(&self, name: &String, records: &[(TableKey<K>, Record)]) -> Result<(), Error> {
+        let table = self.tables.get(name)
+            .ok_or_else(|| Error::NotExistError(format!("Table '{}' name does not exist", name)))?;
+
+        for constraint in table.constraints() {
+            let Constraint::References { field, table: target, .. } = constraint else { continue };
+
+            let target_table = self.tables.get(target)
+                .ok_or_else(|| Error::NotExistError(format!("Referenced table '{}' does not exist", target)))?;
+
+            for (key, record) in records {
+                let value = record.values.get(field)
+                    .ok_or_else(|| Error::MissingFieldError(format!("Record missing required field '{}'", field)))?;
+
+                if let Value::Null = value {
+                    continue;
+                }
+
+                let referenced = TableKey(vec![K::from_value(value)?]);
+
+                let exists = target_table.records().contains_key(&referenced)
+                    || (target == name && records.iter().any(|(staged, _)| staged == &referenced));
+
+                if !exists {
+                    return Err(Error::ConstraintViolationError(format!("Record with key '{}' violates constraint {}: '{}' has no record with key '{}'", key, constraint, target, referenced)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn delete_record(&mut self, name: &String, key: &TableKey<K>) -> Result<(Record, usize), Error> {
+        let table = self.get_table(name)?;
+
+        if !table.records().contains_key(key) {
+            return Err(Error::NotExistError(format!("Table missing a record with key '{}'", key)));
+        }
+
+        let mut deleted: HashMap<String, HashSet<TableKey<K>>> = HashMap::from([(name.clone(), HashSet::from([key.clone()]))]);
+        let mut pending = vec![(name.clone(), key.clone())];
+        let mut restricted = Vec::new();
+        let mut nulled = Vec::new();
+        let mut wave = deleted.clone();
+
+        // Each wave scans the referencing tables once for all keys deleted by the previous wave.
+        while !wave.is_empty() {
+            let mut next_wave: HashMap<String, HashSet<TableKey<K>>> = HashMap::new();
+
+            for (referencing_name, referencing) in &self.tables {
+                for constraint in referencing.constraints() {
+                    let Constraint::References { field, table, on_delete } = constraint else { continue };
+                    let Some(target_keys) = wave.get(table) else { continue };
+
+                    for (referencing_key, record) in referencing.records() {
+                        let Some(target_key) = record.values.get(field)
+                            .and_then(|value| K::from_value(value).ok())
+                            .map(|value| TableKey(vec![value]))
+                            .filter(|target_key| target_keys.contains(target_key)) else { continue };
+
+                        if deleted.get(referencing_name).is_some_and(|keys| keys.contains(referencing_key)) {
+                            continue;
+                        }
+
+                        let entry = (referencing_name.clone(), referencing_key.clone());
+
+                        match on_delete {
+                            OnDelete::Restrict => restricted.push((entry, target_key, constraint.clone())),
+                            OnDelete::Cascade => {
+                                deleted.entry(referencing_name.clone()).or_default().insert(referencing_key.clone());
+                                next_wave.entry(referencing_name.clone()).or_default().insert(referencing_key.clone());
+                                pending.push(entry);
+                            },
+                            OnDelete::SetNull => nulled.push((entry, field.clone())),
+                        }
+                    }
+                }
+            }
+
+            wave = next_wave;
+        }
+
+        let is_deleted = |(table, key): &(String, TableKey<K>)| deleted.get(table).is_some_and(|keys| keys.contains(key));
+
+        if let Some(((referencing_name, referencing_key), target_key, constraint)) = restricted.into_iter().find(|(entry, _, _)| !is_deleted(entry)) {
+            return Err(Error::ConstraintViolationError(format!("Record with key '{}' is still referenced by '{}' record '{}' through constraint {}", target_key, referencing_name, referencing_key, constraint)));
+        }
+
+        for ((target, target_key), field) in nulled.iter().filter(|(entry, _)| !is_deleted(entry)) {
+            let table = self.tables.get_mut(target)
+                .ok_or_else(|| Error::NotExistError(format!("Table '{}' name does not exist", target)))?;
+
+            table.set_null(target_key, field)?;
+        }
+
+        let mut deleted = Vec::new();
+
+        for (target, target_key) in &pending {
+            let table = self.tables.get_mut(target)
+                .ok_or_else(|| Error::NotExistError(format!("Table '{}' name does not exist", target)))?;
+
+            deleted.push(table.delete_record(target_key)?);
+        }
+
+        Ok((deleted.swap_remove(0), pending.len()))
+    }
+
+    pub fn tables(&self) -> &HashMap<String, Table<K>> {
+        &self.tables
+    }
+
     pub fn has_table(&self, name: &String) -> bool {
-        self.tables.contains_key(name)
+        self.tables.contains_key(name) || self.views.contains_key(name)
+    }
+
+    pub fn add_view(&mut self, name: String, select: SelectStatement) -> Result<(), Error> {
+        if is_catalog_name(&name) {
+            return Err(Error::AlreadyExistsError(format!("View name '{}' is reserved for the system catalog", name)));
+        }
+
+        if self.has_table(&name) {
+            return Err(Error::AlreadyExistsError(format!("Table '{}' already exists", name)));
+        }
+
+        self.views.insert(name, select);
+        Ok(())
+    }
+
+    pub fn views(&self) -> &HashMap<String, SelectStatement> {
+        &self.views
+    }
+
+    pub fn source_fields(&self, name: &String) -> Result<HashMap<String, FieldType>, Error> {
+        if let Some(fields) = catalog_fields(name) {
+            return Ok(fields);
+        }
+
+        if let Some(view) = self.views.get(name) {
+            let source_fields = self.source_fields(&view.table)?;
+
+            return Ok(view.fields
+                .iter()
+                .filter(|projection| projection.path.is_empty())
+                .filter_map(|projection| source_fields.get(&projection.field).map(|field_type| (projection.field.clone(), field_type.clone())))
+                .collect());
+        }
+
+        self.tables.get(name)
+            .map(|table| table.fields().clone())
+            .ok_or_else(|| Error::NotExistError(format!("Table '{}' name does not exist", name).to_string()))
+    }
+
+    pub fn is_source_field_nullable(&self, name: &String, field: &String) -> bool {
+        match self.views.get(name) {
+            Some(view) => self.is_source_field_nullable(&view.table, field),
+            None => self.tables.get(name).is_some_and(|table| table.is_nullable(field)),
+        }
+    }
+
+    fn view_dependents(&self, name: &String) -> Vec<&String> {
+        let mut dependents = self.views
+            .iter()
+            .map(|(view, select)| (view, &select.table))
+            .chain(self.materialized_views.iter().map(|(view, materialized)| (view, &materialized.select.table)))
+            .filter(|(_, source)| *source == name)
+            .map(|(view, _)| view)
+            .collect::<Vec<_>>();
+        dependents.sort();
+
+        dependents
+    }
+
+    pub fn drop_view(&mut self, name: &String) -> Result<(), Error> {
+        let dependents = self.view_dependents(name);
+
+        if !dependents.is_empty() {
+            let dependents = dependents.iter().map(|view| format!("'{}'", view)).collect::<Vec<_>>().join(", ");
+            return Err(Error::ConstraintViolationError(format!("View '{}' cannot be dropped because {} select from it", name, dependents)));
+        }
+
+        if self.views.remove(name).is_some() {
+            return Ok(());
+        }
+
+        if self.materialized_views.remove(name).is_some() {
+            self.tables.remove(name);
+            return Ok(());
+        }
+
+        Err(Error::NotExistError(format!("View '{}' does not exist", name)))
+    }
+
+    pub fn add_materialized_view(&mut self, name: String, view: MaterializedView) -> Result<usize, Error> {
+        let table = CreateSelectCommand::build_table(self, view.key.clone(), &view.select.table, &view.select.fields, &view.select.clauses)?;
+        let records = table.len();
+
+        self.add_table(name.clone(), table)?;
+        self.materialized_views.insert(name, view);
+
+        Ok(records)
+    }
+
+    pub fn materialized_views(&self) -> &HashMap<String, MaterializedView> {
+        &self.materialized_views
+    }
+
+    pub fn refresh_view(&mut self, name: &String) -> Result<usize, Error> {
+        let view = self.materialized_views.get(name)
+            .ok_or_else(|| Error::NotExistError(format!("Materialized view '{}' does not exist", name)))?;
+
+        let table = CreateSelectCommand::build_table(self, view.key.clone(), &view.select.table, &view.select.fields, &view.select.clauses)?;
+        let records = table.len();
+
+        self.tables.insert(name.clone(), table);
+
+        Ok(records)
     }
 
     pub fn get_table(&mut self, name: &String) -> Result<&mut Table<K>, Error> {
+        if catalog_fields(name).is_some() {
+            return Err(Error::TypeError(format!("System catalog table '{}' is read-only", name)));
+        }
+
+        if self.views.contains_key(name) || self.materialized_views.contains_key(name) {
+            return Err(Error::TypeError(format!("View '{}' is read-only", name)));
+        }
+
         self.tables.get_mut(name)
             .ok_or_else(|| Error::NotExistError(format!("Table '{}' name does not exist", name).to_string()))
     }
 
+    pub fn get_source(&self, name: &String) -> Result<SelectSource<'_, K>, Error> {
+        if let (Some(fields), Some(records)) = (catalog_fields(name), catalog_records(self, name)) {
+            return Ok(SelectSource::Catalog { fields, slice: TableSlice::new(records) });
+        }
+
+        if let Some(view) = self.views.get(name) {
+            let source = self.get_source(&view.table)?;
+            let result = SelectCommand::run(&source, &view.fields, &view.clauses)?;
+            let fields = self.source_fields(name)?;
+
+            let records = result.rows
+                .into_iter()
+                .map(|row| Record { values: row.values.into_iter().collect() })
+                .collect();
+
+            return Ok(SelectSource::View { fields, slice: TableSlice::new(records) });
+        }
+
+        self.tables.get(name)
+            .map(SelectSource::Table)
+            .ok_or_else(|| Error::NotExistError(format!("Table '{}' name does not exist", name).to_string()))
+    }
+
     pub fn execute_command(&mut self, command: &str) -> Result<ExecutionSuccessValue, Error> {
         let mut executable = parse_command(command, self)?;
         executable.execute()
     }
-    
+
+    pub fn prepare(&self, command: &str) -> Result<PreparedStatement, Error> {
+        prepare_statement(command)
+    }
+
+    pub fn execute_prepared(&mut self, statement: &PreparedStatement, params: &[IntermediateValue]) -> Result<ExecutionSuccessValue, Error> {
+        self.execute_statement(statement.bind(params, now())?)
+    }
+
+    pub fn execute_statement(&mut self, statement: Statement) -> Result<ExecutionSuccessValue, Error> {
+        let mut executable = statement.into_command(self)?;
+        executable.execute()
+    }
+
     pub fn get_session_commands(&self) -> &Vec<String> {
         &self.session_commands
     }
@@ -55,58 +356,62 @@ impl<K: DatabaseKey> Default for Database<K> {
     }
 }
 
-#[derive(Clone, Debug)]
-pub enum AnyDatabase {
-    StringDatabase(Database<String>),
-    IntDatabase(Database<i64>),
+#[derive(Clone, Debug, Default)]
+pub struct AnyDatabase {
+    database: Database<AnyKey>,
 }
 
 impl AnyDatabase {
-    pub fn new(key: KeyType) -> Self {
-        match key {
-            KeyType::String => Self::StringDatabase(Database::new()),
-            KeyType::Int => Self::IntDatabase(Database::new()),
-        }
+    pub fn new() -> Self {
+        Self { database: Database::new() }
     }
 
-    pub fn key_type(&self) -> FieldType {
-        match self {
-            AnyDatabase::StringDatabase(_) => FieldType::String,
-            AnyDatabase::IntDatabase(_) => FieldType::Int,
-        }
+    pub fn database(&self) -> &Database<AnyKey> {
+        &self.database
     }
 
     pub fn execute_command(&mut self, command: &str) -> Result<ExecutionSuccessValue, Error> {
-        let result = match self {
-            AnyDatabase::StringDatabase(db) => db.execute_command(command)?,
-            AnyDatabase::IntDatabase(db) => db.execute_command(command)?,
-        };
+        let statement = prepare_command(command)?;
+        self.execute_prepared(&statement, &[])
+    }
 
-        if !matches!(result, ExecutionSuccessValue::SuccessFileOperation(_)) {
-            match self {
-                AnyDatabase::StringDatabase(db) => db.session_commands.push(command.to_string()),
-                AnyDatabase::IntDatabase(db) => db.session_commands.push(command.to_string()),
-            }
-        }
+    pub fn prepare(&self, command: &str) -> Result<PreparedStatement, Error> {
+        prepare_statement(command)
+    }
+
+    pub fn execute_prepared(&mut self, statement: &PreparedStatement, params: &[IntermediateValue]) -> Result<ExecutionSuccessValue, Error> {
+        let now = now();
+        let command = statement.render(params, now)?;
+        let result = self.database.execute_statement(statement.bind(params, now)?)?;
+
+        self.log_command(&result, command);
 
         Ok(result)
     }
+
+    fn log_command(&mut self, result: &ExecutionSuccessValue, command: String) {
+        if !matches!(result, ExecutionSuccessValue::SuccessFileOperation(_)) {
+            self.database.session_commands.push(command);
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use super::*;
     use crate::database::table::Table;
+    use crate::database::types::FieldType;
 
     #[test]
     fn test_add_table() {
-        let mut db: Database<i64> = Database::new();
+        let mut db: Database<AnyKey> = Database::new();
 
         let table = Table::new(
-            "id".to_string(),
+            vec!["id".to_string()],
             HashMap::from([("id".to_string(), FieldType::Int)]),
             HashMap::new(),
-        );
+        ).unwrap();
 
         assert!(db.add_table("users".to_string(), table.clone()).is_ok());
         assert!(db.has_table(&"users".to_string()));
@@ -115,18 +420,159 @@ mod tests {
         assert!(matches!(duplicate, Err(Error::AlreadyExistsError(_))));
     }
 
+    #[test]
+    fn test_execute_prepared() {
+        let mut db: Database<AnyKey> = Database::new();
+
+        db.execute_command("CREATE users KEY id
+            FIELDS id: Int, name: String").unwrap();
+
+        let insert = db.prepare("INSERT id = ?, name = ? INTO users").unwrap();
+
+        for (id, name) in [(1, "O'Brien"), (2, "Bob")] {
+            let params = [IntermediateValue::Numeric(id as f64), IntermediateValue::String(name.to_string())];
+            db.execute_prepared(&insert, &params).unwrap();
+        }
+
+        let select = db.prepare("SELECT name FROM users WHERE id = $1 OR name = $1").unwrap();
+        let result = db.execute_prepared(&select, &[IntermediateValue::String("O'Brien".to_string())]);
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let select = db.prepare("SELECT name FROM users WHERE id >= $1 AND id < $2").unwrap();
+        let result = db.execute_prepared(&select, &[IntermediateValue::Numeric(1f64), IntermediateValue::Numeric(2f64)]).unwrap();
+        assert_eq!(result.to_string(), "name: \"O'Brien\"\n");
+
+        let result = db.execute_prepared(&select, &[IntermediateValue::Numeric(1f64)]);
+        assert!(matches!(result, Err(Error::NotSpecifiedError(_))));
+
+        let delete = db.prepare("DELETE ? FROM users").unwrap();
+        db.execute_prepared(&delete, &[IntermediateValue::Numeric(2f64)]).unwrap();
+        assert_eq!(db.get_table(&"users".to_string()).unwrap().len(), 1);
+
+        let result = db.execute_command("DELETE ? FROM users");
+        assert!(matches!(result, Err(Error::ParseError(_))));
+    }
+
+    #[test]
+    fn test_any_database_execute_prepared_session_commands() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE users KEY id
+            FIELDS id: Int, name: String").unwrap();
+
+        let insert = db.prepare("INSERT id = $1, name = $2 INTO users").unwrap();
+        db.execute_prepared(&insert, &[IntermediateValue::Numeric(1f64), IntermediateValue::String("Alice".to_string())]).unwrap();
+
+        let quoted = "Say \"hi\" \\ bye".to_string();
+        db.execute_prepared(&insert, &[IntermediateValue::Numeric(2f64), IntermediateValue::String(quoted.clone())]).unwrap();
+
+        assert_eq!(db.database().get_session_commands().len(), 3);
+        assert_eq!(db.database().get_session_commands()[1], "INSERT id = 1, name = \"Alice\" INTO users");
+        assert_eq!(db.database().get_session_commands()[2], "INSERT id = 2, name = \"Say \\\"hi\\\" \\\\ bye\" INTO users");
+
+        db.execute_command("SAVE_AS prepared_session_test_output.txt").unwrap();
+
+        let mut restored = AnyDatabase::new();
+        restored.execute_command("READ_FROM prepared_session_test_output.txt").unwrap();
+        fs::remove_file("prepared_session_test_output.txt").unwrap();
+
+        let select = restored.prepare("SELECT name FROM users WHERE name = ?").unwrap();
+        let result = restored.execute_prepared(&select, &[IntermediateValue::String(quoted)]).unwrap();
+        assert_eq!(result.affected_rows(), 1);
+    }
+
+    #[test]
+    fn test_any_database_logs_padded_multibyte_commands() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE `städte` KEY id
+            FIELDS id: Int, name: String").unwrap();
+
+        let insert = db.prepare("  \t INSERT id = ?, name = \"Zürich\" INTO `städte`  ").unwrap();
+        db.execute_prepared(&insert, &[IntermediateValue::Numeric(1f64)]).unwrap();
+
+        let insert = db.prepare("   INSERT name = ?, id = ? INTO `städte`").unwrap();
+        db.execute_prepared(&insert, &[IntermediateValue::String("Genève \"ville\"".to_string()), IntermediateValue::Numeric(2f64)]).unwrap();
+
+        let commands = db.database().get_session_commands();
+        assert_eq!(commands[1], "INSERT id = 1, name = \"Zürich\" INTO `städte`");
+        assert_eq!(commands[2], "INSERT name = \"Genève \\\"ville\\\"\", id = 2 INTO `städte`");
+
+        db.execute_command("SAVE_AS multibyte_session_test_output.txt").unwrap();
+
+        let mut restored = AnyDatabase::new();
+        restored.execute_command("READ_FROM multibyte_session_test_output.txt").unwrap();
+        fs::remove_file("multibyte_session_test_output.txt").unwrap();
+
+        let select = "SELECT id, name FROM `städte` ORDER_BY id";
+        assert_eq!(restored.execute_command(select).unwrap().to_string(), "id: 1, name: \"Zürich\"\nid: 2, name: \"Genève \"ville\"\"\n");
+    }
+
+    #[test]
+    fn test_any_database_logs_resolved_current_time() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE events KEY id
+            FIELDS id: Int, at: Timestamp").unwrap();
+        db.execute_command("INSERT id = 1, at = NOW() INTO events").unwrap();
+
+        let insert = db.prepare("INSERT id = ?, at = NOW() - 1 DAYS INTO events").unwrap();
+        db.execute_prepared(&insert, &[IntermediateValue::Numeric(2f64)]).unwrap();
+
+        let commands = db.database().get_session_commands();
+        assert!(commands[1].starts_with("INSERT id = 1, at = TIMESTAMP \""));
+        assert!(commands[2].starts_with("INSERT id = 2, at = TIMESTAMP \""));
+
+        db.execute_command("SAVE_AS current_time_session_test_output.txt").unwrap();
+
+        let mut restored = AnyDatabase::new();
+        restored.execute_command("READ_FROM current_time_session_test_output.txt").unwrap();
+        fs::remove_file("current_time_session_test_output.txt").unwrap();
+
+        let select = "SELECT id, at FROM events ORDER_BY id";
+        assert_eq!(restored.execute_command(select).unwrap().to_string(), db.execute_command(select).unwrap().to_string());
+    }
+
     #[test]
     fn test_any_database_execute_session_commands() {
-        let mut db = AnyDatabase::new(KeyType::Int);
+        let mut db = AnyDatabase::new();
 
         let result = db.execute_command("CREATE users KEY id
             FIELDS id: Int");
 
         assert!(result.is_ok());
 
-        if let AnyDatabase::IntDatabase(inner) = db {
-            assert_eq!(inner.get_session_commands().len(), 1);
-            assert!(inner.has_table(&"users".to_string()))
-        }
+        assert_eq!(db.database().get_session_commands().len(), 1);
+        assert!(db.database().has_table(&"users".to_string()));
+    }
+
+    #[test]
+    fn test_any_database_per_table_key_types() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE users KEY name
+            FIELDS name: String, age: Int").unwrap();
+        db.execute_command("CREATE events KEY id
+            FIELDS id: Int, user: String").unwrap();
+        db.execute_command("CREATE sessions KEY token
+            FIELDS token: Uuid, user: String").unwrap();
+
+        db.execute_command("INSERT name = \"alice\", age = 30 INTO users").unwrap();
+        db.execute_command("INSERT id = 1, user = \"alice\" INTO events").unwrap();
+        db.execute_command("INSERT token = \"123e4567-e89b-12d3-a456-426614174000\", user = \"alice\" INTO sessions").unwrap();
+
+        let result = db.execute_command("INSERT name = 1, age = 30 INTO users");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("DELETE 1 FROM users");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        db.execute_command("DELETE \"alice\" FROM users").unwrap();
+        db.execute_command("DELETE 1 FROM events").unwrap();
+        db.execute_command("DELETE \"123e4567-e89b-12d3-a456-426614174000\" FROM sessions").unwrap();
+
+        let result = db.execute_command("CREATE prices KEY amount
+            FIELDS amount: Float");
+        assert!(matches!(result, Err(Error::TypeError(_))));
     }
 }
@@ -0,0 +1,228 @@
+﻿use std::cmp::Ordering;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::errors::Error;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+fn pow10(exponent: u32) -> i128 {
+    10i128.pow(exponent)
+}
+
+impl Decimal {
+    pub const MAX_PRECISION: u32 = 38;
+
+    pub fn new(mantissa: i128, scale: u32) -> Result<Self, Error> {
+        if scale > Self::MAX_PRECISION {
+            return Err(Error::TypeError(format!("Decimal scale {} exceeds {}", scale, Self::MAX_PRECISION)));
+        }
+
+        Ok(Self { mantissa, scale })
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn precision(&self) -> u32 {
+        let digits = self.mantissa.unsigned_abs().checked_ilog10().map_or(1, |log| log + 1);
+        digits.max(self.scale)
+    }
+
+    pub fn is_integral(&self) -> bool {
+        self.mantissa % pow10(self.scale) == 0
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        match self.is_integral() {
+            true => i64::try_from(self.mantissa / pow10(self.scale)).ok(),
+            false => None,
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+
+    pub fn rescale(&self, scale: u32) -> Result<Self, Error> {
+        let mantissa = match scale.cmp(&self.scale) {
+            Ordering::Equal => Some(self.mantissa),
+            Ordering::Greater => self.mantissa.checked_mul(pow10(scale - self.scale)),
+            Ordering::Less => {
+                let divisor = pow10(self.scale - scale);
+                match self.mantissa % divisor {
+                    0 => Some(self.mantissa / divisor),
+                    _ => return Err(Error::TypeError(format!("Decimal {} cannot be stored with scale {} without rounding", self, scale))),
+                }
+            },
+        };
+
+        let mantissa = mantissa
+            .ok_or_else(|| Error::TypeError(format!("Decimal {} overflows with scale {}", self, scale)))?;
+
+        Self::new(mantissa, scale)
+    }
+
+    pub fn fit(&self, precision: u32, scale: u32) -> Result<Self, Error> {
+        let decimal = self.rescale(scale)?;
+
+        if decimal.precision() > precision {
+            return Err(Error::TypeError(format!("Decimal {} does not fit Decimal({}, {})", self, precision, scale)));
+        }
+
+        Ok(decimal)
+    }
+
+    fn align(&self, other: &Self) -> Option<(i128, i128, u32)> {
+        let scale = self.scale.max(other.scale);
+        let left = self.mantissa.checked_mul(pow10(scale - self.scale))?;
+        let right = other.mantissa.checked_mul(pow10(scale - other.scale))?;
+
+        Some((left, right, scale))
+    }
+
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let (left, right, scale) = self.align(other)?;
+        Some(Self { mantissa: left.checked_add(right)?, scale })
+    }
+
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        let (left, right, scale) = self.align(other)?;
+        Some(Self { mantissa: left.checked_sub(right)?, scale })
+    }
+
+    pub fn checked_mul(&self, other: &Self) -> Option<Self> {
+        let scale = self.scale + other.scale;
+        if scale > Self::MAX_PRECISION {
+            return None;
+        }
+
+        Some(Self { mantissa: self.mantissa.checked_mul(other.mantissa)?, scale })
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(value: i64) -> Self {
+        Self { mantissa: value as i128, scale: 0 }
+    }
+}
+
+impl FromStr for Decimal {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::ParseError(format!("Invalid decimal '{}'", input));
+
+        let (negative, digits) = match input.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, input),
+        };
+        let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+
+        if integer.is_empty() || !integer.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(invalid());
+        }
+
+        let mantissa = format!("{}{}", integer, fraction)
+            .parse::<i128>()
+            .map_err(|_| invalid())?;
+
+        Self::new(if negative { -mantissa } else { mantissa }, fraction.len() as u32)
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        let (left_integer, left_fraction) = (self.mantissa / pow10(self.scale), self.mantissa % pow10(self.scale));
+        let (right_integer, right_fraction) = (other.mantissa / pow10(other.scale), other.mantissa % pow10(other.scale));
+
+        left_integer.cmp(&right_integer).then_with(|| {
+            let left = left_fraction * pow10(scale - self.scale);
+            let right = right_fraction * pow10(scale - other.scale);
+            left.cmp(&right)
+        })
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.mantissa < 0 { "-" } else { "" };
+        let digits = self.mantissa.unsigned_abs();
+        let divisor = pow10(self.scale).unsigned_abs();
+
+        match self.scale {
+            0 => write!(f, "{}{}", sign, digits),
+            scale => write!(f, "{}{}.{:0width$}", sign, digits / divisor, digits % divisor, width = scale as usize),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(input: &str) -> Decimal {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        assert_eq!(decimal("12.50").to_string(), "12.50");
+        assert_eq!(decimal("-0.05").to_string(), "-0.05");
+        assert_eq!(decimal("99999999999999999999.99").to_string(), "99999999999999999999.99");
+        assert!("1.2.3".parse::<Decimal>().is_err());
+        assert!(".5".parse::<Decimal>().is_err());
+    }
+
+    #[test]
+    fn test_exact_arithmetic_and_comparison() {
+        let total = (0..10).fold(Decimal::from(0), |sum, _| sum.checked_add(&decimal("0.10")).unwrap());
+        assert_eq!(total, decimal("1.00"));
+        assert_eq!(total, Decimal::from(1));
+
+        assert_eq!(decimal("19.99").checked_mul(&decimal("3")).unwrap().to_string(), "59.97");
+        assert_eq!(decimal("0.1").checked_sub(&decimal("0.3")).unwrap().to_string(), "-0.2");
+        assert!(decimal("99999999999999999999999999999999999999").checked_add(&decimal("99999999999999999999999999999999999999")).is_none());
+        assert!(decimal("0.0000000000000000000001").checked_mul(&decimal("0.0000000000000000000001")).is_none());
+
+        assert_eq!(decimal("1.00"), Decimal::from(1));
+        assert_eq!(decimal("0.10"), decimal("0.1"));
+        assert!(decimal("0.30") > decimal("0.299"));
+        assert!(decimal("-1.5") < decimal("-1.25"));
+        assert!(decimal("-0.5") < decimal("0.3"));
+    }
+
+    #[test]
+    fn test_fit() {
+        assert_eq!(decimal("5").fit(5, 2).unwrap().to_string(), "5.00");
+        assert!(decimal("1.005").fit(5, 2).is_err());
+        assert!(decimal("1234.5").fit(5, 2).is_err());
+        assert_eq!(decimal("123.4").fit(5, 2).unwrap().to_string(), "123.40");
+    }
+
+    #[test]
+    fn test_to_f64_matches_float_literals() {
+        assert_eq!(decimal("0.1").to_f64(), 0.1);
+        assert_eq!(decimal("0.00000000000000000000001").to_f64(), 1e-23);
+        assert_eq!(decimal("123456789012345678.9").to_f64(), 123456789012345678.9);
+    }
+}
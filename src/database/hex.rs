@@ -0,0 +1,36 @@
+﻿use crate::errors::Error;
+
+pub fn parse_hex(input: &str) -> Result<Vec<u8>, Error> {
+    if let Some(c) = input.chars().find(|c| !c.is_ascii_hexdigit()) {
+        return Err(Error::ParseError(format!("Invalid hex digit '{}' in '{}'", c, input)));
+    }
+
+    if !input.len().is_multiple_of(2) {
+        return Err(Error::ParseError(format!("Expected an even number of hex digits in '{}'", input)));
+    }
+
+    (0..input.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&input[i..i + 2], 16)
+            .map_err(|e| Error::ParseError(format!("Invalid hex string '{}': {}", input, e))))
+        .collect()
+}
+
+pub fn format_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format() {
+        assert_eq!(parse_hex("DEADbeef").unwrap(), vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(format_hex(&[0, 255]), "00ff");
+
+        assert!(matches!(parse_hex("abc"), Err(Error::ParseError(message)) if message == "Expected an even number of hex digits in 'abc'"));
+        assert!(matches!(parse_hex("0g"), Err(Error::ParseError(message)) if message == "Invalid hex digit 'g' in '0g'"));
+        assert!(parse_hex("é0").is_err());
+    }
+}
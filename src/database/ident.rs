@@ -0,0 +1,21 @@
+﻿use crate::database::value::escape_string;
+
+// Keywords are not reserved: every place a name can appear is followed by a token that tells them apart.
+pub fn is_bare_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    matches!(chars.next(), Some(first) if first.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+pub fn quote_ident(name: &str) -> String {
+    match is_bare_ident(name) {
+        true => name.to_string(),
+        false => format!("`{}`", name.replace('`', "``")),
+    }
+}
+
+pub fn format_json_path(field: &str, keys: &[String]) -> String {
+    let path = keys.iter().map(|key| format!("->\"{}\"", escape_string(key))).collect::<String>();
+    format!("{}{}", quote_ident(field), path)
+}
@@ -0,0 +1,117 @@
+﻿use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Bound;
+use crate::commands::clauses::evaluable::CompOp;
+use crate::database::key::{DatabaseKey, TableKey};
+use crate::database::types::FieldType;
+use crate::database::value::{sort_compare_values, Value};
+
+#[derive(Clone, Debug)]
+pub struct IndexValue(pub Value);
+
+impl PartialEq for IndexValue {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for IndexValue {}
+
+impl PartialOrd for IndexValue {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for IndexValue {
+    fn cmp(&self, other: &Self) -> Ordering {
+        sort_compare_values(&Some(&self.0), &Some(&other.0))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Index<K: DatabaseKey> {
+    entries: BTreeMap<IndexValue, BTreeSet<TableKey<K>>>,
+}
+
+impl<K: DatabaseKey> Index<K> {
+    pub fn new() -> Self {
+        Self { entries: BTreeMap::new() }
+    }
+
+    pub fn supports(field_type: &FieldType) -> bool {
+        !matches!(field_type, FieldType::List(_) | FieldType::Json)
+    }
+
+    pub fn insert(&mut self, value: &Value, key: &TableKey<K>) {
+        self.entries.entry(IndexValue(value.clone())).or_default().insert(key.clone());
+    }
+
+    pub fn remove(&mut self, value: &Value, key: &TableKey<K>) {
+        let value = IndexValue(value.clone());
+
+        if let Some(keys) = self.entries.get_mut(&value) {
+            keys.remove(key);
+
+            if keys.is_empty() {
+                self.entries.remove(&value);
+            }
+        }
+    }
+
+    pub fn matching(&self, value: &Value) -> impl Iterator<Item = &TableKey<K>> {
+        self.entries.get(&IndexValue(value.clone())).into_iter().flatten()
+    }
+
+    pub fn lookup(&self, op: &CompOp, value: &Value) -> Option<BTreeSet<&TableKey<K>>> {
+        let value = IndexValue(value.clone());
+
+        let range = match op {
+            CompOp::Eq => (Bound::Included(&value), Bound::Included(&value)),
+            CompOp::Lt => (Bound::Unbounded, Bound::Excluded(&value)),
+            CompOp::Leq => (Bound::Unbounded, Bound::Included(&value)),
+            CompOp::Gt => (Bound::Excluded(&value), Bound::Unbounded),
+            CompOp::Geq => (Bound::Included(&value), Bound::Unbounded),
+            CompOp::Neq | CompOp::Contains | CompOp::Is | CompOp::IsNot => return None,
+        };
+
+        Some(self.entries.range::<IndexValue, _>(range).flat_map(|(_, keys)| keys).collect())
+    }
+}
+
+impl<K: DatabaseKey> Default for Index<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::database::key::AnyKey;
+    use super::*;
+
+    fn keys(index: &Index<AnyKey>, op: CompOp, year: i64) -> Vec<i64> {
+        index.lookup(&op, &Value::Int(year)).unwrap().into_iter().map(|key| match key.0[0] {
+            AnyKey::Int(key) => key,
+            _ => panic!("Expected an Int key"),
+        }).collect()
+    }
+
+    #[test]
+    fn test_index_lookup() {
+        let mut index = Index::<AnyKey>::new();
+
+        for (key, year) in [(1, 1999), (2, 2001), (3, 2001), (4, 2010)] {
+            index.insert(&Value::Int(year), &TableKey(vec![AnyKey::Int(key)]));
+        }
+
+        assert_eq!(keys(&index, CompOp::Eq, 2001), vec![2, 3]);
+        assert_eq!(keys(&index, CompOp::Lt, 2001), vec![1]);
+        assert_eq!(keys(&index, CompOp::Geq, 2001), vec![2, 3, 4]);
+        assert_eq!(keys(&index, CompOp::Gt, 2010), Vec::<i64>::new());
+        assert!(index.lookup(&CompOp::Neq, &Value::Int(2001)).is_none());
+
+        index.remove(&Value::Int(2001), &TableKey(vec![AnyKey::Int(2)]));
+        assert_eq!(keys(&index, CompOp::Eq, 2001), vec![3]);
+    }
+}
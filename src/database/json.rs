@@ -0,0 +1,395 @@
+﻿use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::database::value::Value;
+use crate::errors::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(BTreeMap<String, Json>),
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(members) => members.get(key),
+            Json::Array(items) => key.parse::<usize>().ok().and_then(|index| items.get(index)),
+            _ => None,
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            Json::Bool(b) => Value::Bool(*b),
+            Json::Int(i) => Value::Int(*i),
+            Json::Float(f) => Value::Float(*f),
+            Json::String(s) => Value::String(s.clone()),
+            other => Value::Json(other.clone()),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Json::Null => 0,
+            Json::Bool(_) => 1,
+            Json::Int(_) | Json::Float(_) => 2,
+            Json::String(_) => 3,
+            Json::Array(_) => 4,
+            Json::Object(_) => 5,
+        }
+    }
+
+    pub fn compare(&self, other: &Json) -> Ordering {
+        match (self, other) {
+            (Json::Bool(a), Json::Bool(b)) => a.cmp(b),
+            (Json::Int(a), Json::Int(b)) => a.cmp(b),
+            (Json::Int(a), Json::Float(b)) => (*a as f64).total_cmp(b),
+            (Json::Float(a), Json::Int(b)) => a.total_cmp(&(*b as f64)),
+            (Json::Float(a), Json::Float(b)) => a.total_cmp(b),
+            (Json::String(a), Json::String(b)) => a.cmp(b),
+            (Json::Array(a), Json::Array(b)) => a.iter()
+                .zip(b.iter())
+                .map(|(x, y)| x.compare(y))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (Json::Object(a), Json::Object(b)) => a.iter()
+                .zip(b.iter())
+                .map(|((ka, va), (kb, vb))| ka.cmp(kb).then_with(|| va.compare(vb)))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (a, b) => a.rank().cmp(&b.rank()),
+        }
+    }
+}
+
+struct JsonParser<'a> {
+    input: &'a str,
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, bytes: input.as_bytes(), position: 0 }
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::TypeError(format!("Invalid JSON: {} at position {}", message, self.position))
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.peek() {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.position).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), Error> {
+        if self.peek() != Some(byte) {
+            return Err(self.error(&format!("expected '{}'", byte as char)));
+        }
+
+        self.position += 1;
+        Ok(())
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: Json) -> Result<Json, Error> {
+        if !self.input[self.position..].starts_with(literal) {
+            return Err(self.error("unexpected token"));
+        }
+
+        self.position += literal.len();
+        Ok(value)
+    }
+
+    fn parse_value(&mut self) -> Result<Json, Error> {
+        self.skip_whitespace();
+
+        let value = match self.peek() {
+            Some(b'n') => self.parse_literal("null", Json::Null)?,
+            Some(b't') => self.parse_literal("true", Json::Bool(true))?,
+            Some(b'f') => self.parse_literal("false", Json::Bool(false))?,
+            Some(b'"') => Json::String(self.parse_string()?),
+            Some(b'[') => self.parse_array()?,
+            Some(b'{') => self.parse_object()?,
+            Some(b'-' | b'0'..=b'9') => self.parse_number()?,
+            Some(_) => return Err(self.error("unexpected character")),
+            None => return Err(self.error("unexpected end of input")),
+        };
+
+        self.skip_whitespace();
+        Ok(value)
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.position;
+        while let Some(b'0'..=b'9') = self.peek() {
+            self.position += 1;
+        }
+        self.position - start
+    }
+
+    fn parse_number(&mut self) -> Result<Json, Error> {
+        let start = self.position;
+        let mut integral = true;
+
+        if self.peek() == Some(b'-') {
+            self.position += 1;
+        }
+
+        let first = self.peek();
+        let digits = self.skip_digits();
+        if digits == 0 || (digits > 1 && first == Some(b'0')) {
+            return Err(self.error("invalid number"));
+        }
+
+        if self.peek() == Some(b'.') {
+            integral = false;
+            self.position += 1;
+            if self.skip_digits() == 0 {
+                return Err(self.error("invalid number"));
+            }
+        }
+
+        if let Some(b'e' | b'E') = self.peek() {
+            integral = false;
+            self.position += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.position += 1;
+            }
+            if self.skip_digits() == 0 {
+                return Err(self.error("invalid number"));
+            }
+        }
+
+        let text = &self.input[start..self.position];
+
+        if integral && let Ok(i) = text.parse::<i64>() {
+            return Ok(Json::Int(i));
+        }
+
+        match text.parse::<f64>() {
+            Ok(number) if number.is_finite() => Ok(Json::Float(number)),
+            Ok(_) => Err(self.error("number out of range")),
+            Err(_) => Err(self.error("invalid number")),
+        }
+    }
+
+    fn parse_hex_escape(&mut self) -> Result<u32, Error> {
+        let hex = self.input.get(self.position..self.position + 4)
+            .filter(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()))
+            .ok_or_else(|| self.error("invalid unicode escape"))?;
+
+        self.position += 4;
+        u32::from_str_radix(hex, 16).map_err(|_| self.error("invalid unicode escape"))
+    }
+
+    fn parse_string(&mut self) -> Result<String, Error> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+
+        loop {
+            let rest = &self.input[self.position..];
+            let c = rest.chars().next().ok_or_else(|| self.error("unterminated string"))?;
+            self.position += c.len_utf8();
+
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let escape = self.peek().ok_or_else(|| self.error("unterminated string"))?;
+                    self.position += 1;
+
+                    match escape {
+                        b'"' => out.push('"'),
+                        b'\\' => out.push('\\'),
+                        b'/' => out.push('/'),
+                        b'b' => out.push('\u{8}'),
+                        b'f' => out.push('\u{c}'),
+                        b'n' => out.push('\n'),
+                        b'r' => out.push('\r'),
+                        b't' => out.push('\t'),
+                        b'u' => {
+                            let mut code = self.parse_hex_escape()?;
+
+                            if (0xD800..0xDC00).contains(&code) {
+                                self.expect(b'\\')?;
+                                self.expect(b'u')?;
+                                let low = self.parse_hex_escape()?;
+                                if !(0xDC00..0xE000).contains(&low) {
+                                    return Err(self.error("invalid surrogate pair"));
+                                }
+                                code = 0x10000 + ((code - 0xD800) << 10) + (low - 0xDC00);
+                            }
+
+                            out.push(char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))?);
+                        },
+                        _ => return Err(self.error("invalid escape")),
+                    }
+                },
+                c if (c as u32) < 0x20 => return Err(self.error("control character in string")),
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Json, Error> {
+        self.expect(b'[')?;
+        self.skip_whitespace();
+
+        let mut items = Vec::new();
+        if self.peek() == Some(b']') {
+            self.position += 1;
+            return Ok(Json::Array(items));
+        }
+
+        loop {
+            items.push(self.parse_value()?);
+
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b']') => { self.position += 1; return Ok(Json::Array(items)); },
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Json, Error> {
+        self.expect(b'{')?;
+        self.skip_whitespace();
+
+        let mut members = BTreeMap::new();
+        if self.peek() == Some(b'}') {
+            self.position += 1;
+            return Ok(Json::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+
+            if members.insert(key.clone(), self.parse_value()?).is_some() {
+                return Err(self.error(&format!("duplicate key \"{}\"", key)));
+            }
+
+            match self.peek() {
+                Some(b',') => self.position += 1,
+                Some(b'}') => { self.position += 1; return Ok(Json::Object(members)); },
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+}
+
+impl FromStr for Json {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut parser = JsonParser::new(input);
+        let value = parser.parse_value()?;
+
+        if parser.position != input.len() {
+            return Err(parser.error("trailing characters"));
+        }
+
+        Ok(value)
+    }
+}
+
+fn write_string(f: &mut Formatter<'_>, s: &str) -> std::fmt::Result {
+    write!(f, "\"")?;
+
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            // Single quotes delimit JSON literals in commands, so they are always escaped.
+            c if c == '\'' || (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+
+    write!(f, "\"")
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(b) => write!(f, "{}", b),
+            Json::Int(i) => write!(f, "{}", i),
+            Json::Float(fl) if fl.fract() == 0.0 => write!(f, "{}.0", fl),
+            Json::Float(fl) => write!(f, "{}", fl),
+            Json::String(s) => write_string(f, s),
+            Json::Array(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            },
+            Json::Object(members) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ": {}", value)?;
+                }
+                write!(f, "}}")
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let json = r#" {"user": {"id": 7, "name": "O'Brien\n"}, "tags": ["a", 1.5, true, null], "n": -2e3} "#.parse::<Json>().unwrap();
+        assert_eq!(json.to_string(), r#"{"n": -2000.0, "tags": ["a", 1.5, true, null], "user": {"id": 7, "name": "O\u0027Brien\n"}}"#);
+        assert_eq!(json.to_string().parse::<Json>().unwrap(), json);
+
+        assert_eq!(json.get("user").and_then(|user| user.get("id")), Some(&Json::Int(7)));
+        assert_eq!(json.get("tags").and_then(|tags| tags.get("1")), Some(&Json::Float(1.5)));
+        assert_eq!(json.get("missing"), None);
+
+        assert_eq!("\"\\ud83d\\ude00\"".parse::<Json>().unwrap(), Json::String("😀".to_string()));
+
+        let json = "[1e300, -0.5e-3, 18446744073709551616]".parse::<Json>().unwrap();
+        assert_eq!(json.to_string().parse::<Json>().unwrap(), json);
+        assert!(!json.to_string().contains('e'));
+
+        for invalid in ["1e400", "-1e400", "", "{", "[1,]", "{\"a\" 1}", "01", "\"\\x\"", "nul", "1 2", "{\"a\": 1, \"a\": 2}"] {
+            assert!(matches!(invalid.parse::<Json>(), Err(Error::TypeError(_))), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(Json::Int(1).compare(&Json::Float(1.0)), Ordering::Equal);
+        assert_eq!(Json::Null.compare(&Json::Bool(false)), Ordering::Less);
+        assert_eq!("[1, 2]".parse::<Json>().unwrap().compare(&"[1, 2, 0]".parse().unwrap()), Ordering::Less);
+        assert_eq!("{\"a\": 2}".parse::<Json>().unwrap().compare(&"{\"a\": 1}".parse().unwrap()), Ordering::Greater);
+    }
+}
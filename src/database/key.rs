@@ -1,59 +1,58 @@
-﻿use std::fmt::Display;
+﻿use std::fmt::{Display, Formatter};
 use std::hash::Hash;
-use crate::database::types::{FieldType, KeyType};
-use crate::database::value::{KeyValue, Value};
+use crate::database::types::FieldType;
+use crate::database::uuid::Uuid;
+use crate::database::value::Value;
 use crate::errors::Error;
 
-pub trait DatabaseKey: Eq + Ord + Sized + Hash + Display {
-    fn get_key_type() -> KeyType;
-    fn get_field_type() -> FieldType;
+pub trait DatabaseKey: Clone + Eq + Ord + Sized + Hash + Display {
+    fn supports(field_type: &FieldType) -> bool;
     fn from_value(v: &Value) -> Result<Self, Error>;
-    fn from_key_value(v: &KeyValue) -> Result<Self, Error>;
 }
 
-impl DatabaseKey for String {
-    fn get_key_type() -> KeyType {
-        KeyType::String
-    }
-
-    fn get_field_type() -> FieldType {
-        FieldType::String
-    }
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TableKey<K: DatabaseKey>(pub Vec<K>);
 
-    fn from_value(v: &Value) -> Result<Self, Error> {
-        match v {
-            Value::String(s) => Ok(s.clone()),
-            _ => Err(Error::TypeError("Expected string key".to_string()))
+impl<K: DatabaseKey> Display for TableKey<K> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0.as_slice() {
+            [single] => write!(f, "{}", single),
+            parts => write!(f, "({})", parts.iter().map(|part| part.to_string()).collect::<Vec<_>>().join(", ")),
         }
     }
+}
 
-    fn from_key_value(v: &KeyValue) -> Result<Self, Error> {
-        match v {
-            KeyValue::String(s) => Ok(s.clone()),
-            _ => Err(Error::TypeError("Expected string key".to_string()))
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum AnyKey {
+    String(String),
+    Int(i64),
+    Uuid(Uuid),
+}
+
+impl Display for AnyKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnyKey::String(s) => write!(f, "{}", s),
+            AnyKey::Int(i) => write!(f, "{}", i),
+            AnyKey::Uuid(u) => write!(f, "{}", u),
         }
     }
 }
-impl DatabaseKey for i64 {
-    fn get_key_type() -> KeyType {
-        KeyType::Int
-    }
 
-    fn get_field_type() -> FieldType {
-        FieldType::Int
+impl DatabaseKey for AnyKey {
+    fn supports(field_type: &FieldType) -> bool {
+        matches!(field_type, FieldType::String | FieldType::Int | FieldType::Uuid)
     }
 
     fn from_value(v: &Value) -> Result<Self, Error> {
         match v {
-            Value::Int(i) => Ok(*i),
-            _ => Err(Error::TypeError("Expected integer key".to_string()))
-        }
-    }
-
-    fn from_key_value(v: &KeyValue) -> Result<Self, Error> {
-        match v {
-            KeyValue::Int(i) => Ok(*i),
-            _ => Err(Error::TypeError("Expected integer key".to_string()))
+            Value::String(s) => Ok(AnyKey::String(s.clone())),
+            Value::Int(i) => Ok(AnyKey::Int(*i)),
+            Value::Uuid(u) => Ok(AnyKey::Uuid(*u)),
+            other => {
+                let field_type = other.get_field_type().map_or_else(|| "NULL".to_string(), |field_type| field_type.to_string());
+                Err(Error::TypeError(format!("{} cannot be used as a key", field_type)))
+            },
         }
     }
 }
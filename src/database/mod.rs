@@ -1,7 +1,18 @@
-﻿pub mod databases;
+﻿pub mod catalog;
+pub mod constraint;
+pub mod databases;
+pub mod decimal;
+pub mod hex;
+pub mod ident;
+pub mod index;
+pub mod json;
 pub mod key;
 pub mod record;
 pub mod table;
 pub mod types;
 pub mod value;
-pub mod slice;
\ No newline at end of file
+pub mod slice;
+pub mod source;
+pub mod view;
+pub mod temporal;
+pub mod uuid;
\ No newline at end of file
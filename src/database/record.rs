@@ -1,4 +1,6 @@
 ﻿use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use crate::database::ident::format_json_path;
 use crate::database::slice::SelectRow;
 use crate::database::value::{IntermediateValue, Value};
 use crate::errors::Error;
@@ -8,20 +10,58 @@ pub struct IntermediateRecord {
     pub values: HashMap<String, IntermediateValue>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Projection {
+    pub field: String,
+    pub path: Vec<String>,
+}
+
+impl Projection {
+    pub fn new(field: String, path: Vec<String>) -> Self {
+        Self { field, path }
+    }
+}
+
+impl From<String> for Projection {
+    fn from(field: String) -> Self {
+        Self { field, path: Vec::new() }
+    }
+}
+
+impl From<&str> for Projection {
+    fn from(field: &str) -> Self {
+        Self::from(field.to_string())
+    }
+}
+
+impl Display for Projection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.path.is_empty() {
+            true => write!(f, "{}", self.field),
+            false => write!(f, "{}", format_json_path(&self.field, &self.path)),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Record {
     pub values: HashMap<String, Value>,
 }
 
 impl Record {
-    pub fn project_fields(&self, fields: &[String]) -> Result<SelectRow, Error> {
+    pub fn project_fields(&self, projections: &[Projection]) -> Result<SelectRow, Error> {
         let mut out = Vec::new();
 
-        for field in fields {
-            let val = self.values.get(field)
-                .ok_or_else(|| Error::MissingFieldError(format!("Field '{}' does not exist", field)))?;
+        for projection in projections {
+            let val = self.values.get(&projection.field)
+                .ok_or_else(|| Error::MissingFieldError(format!("Field '{}' does not exist", projection.field)))?;
+
+            let val = match projection.path.is_empty() {
+                true => val.clone(),
+                false => val.json_path(&projection.path)?,
+            };
 
-            out.push((field.clone(), val.clone()));
+            out.push((projection.to_string(), val));
         }
 
         Ok(SelectRow { values: out })
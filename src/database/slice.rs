@@ -1,6 +1,7 @@
 ﻿use std::fmt::{Display, Formatter};
-use crate::database::record::Record;
-use crate::database::value::Value;
+use crate::database::record::{IntermediateRecord, Projection, Record};
+use crate::database::value::{IntermediateValue, Value};
+use crate::errors::Error;
 
 #[derive(Clone, Debug)]
 pub struct TableSlice {
@@ -18,6 +19,17 @@ pub struct SelectRow {
     pub values: Vec<(String, Value)>,
 }
 
+impl SelectRow {
+    pub fn to_intermediate_record(&self) -> IntermediateRecord {
+        let values = self.values
+            .iter()
+            .map(|(field, value)| (field.clone(), IntermediateValue::from(value)))
+            .collect();
+
+        IntermediateRecord { values }
+    }
+}
+
 impl Display for SelectRow {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let values = self.values
@@ -35,6 +47,23 @@ pub struct SelectResult {
     pub rows: Vec<SelectRow>,
 }
 
+impl SelectResult {
+    pub fn from_records(records: &[Record], fields: &[Projection]) -> Result<Self, Error> {
+        let mut rows = Vec::new();
+
+        for record in records {
+            rows.push(record.project_fields(fields)?);
+        }
+
+        Ok(Self { rows })
+    }
+
+    pub fn from_fields(records: &[Record], fields: &[String]) -> Result<Self, Error> {
+        let projections = fields.iter().cloned().map(Projection::from).collect::<Vec<_>>();
+        Self::from_records(records, &projections)
+    }
+}
+
 impl Display for SelectResult {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let rows = self.rows
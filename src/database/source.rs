@@ -0,0 +1,73 @@
+﻿use std::collections::HashMap;
+use crate::commands::clauses::clause::AnyClause;
+use crate::commands::clauses::evaluable::AnyEvaluable;
+use crate::database::key::DatabaseKey;
+use crate::database::slice::TableSlice;
+use crate::database::table::Table;
+use crate::database::types::FieldType;
+
+pub enum SelectSource<'a, K: DatabaseKey> {
+    Table(&'a Table<K>),
+    Catalog { fields: HashMap<String, FieldType>, slice: TableSlice },
+    View { fields: HashMap<String, FieldType>, slice: TableSlice },
+}
+
+impl<K: DatabaseKey> SelectSource<'_, K> {
+    pub fn to_slice(&self) -> TableSlice {
+        match self {
+            SelectSource::Table(table) => table.to_slice(),
+            SelectSource::Catalog { slice, .. } | SelectSource::View { slice, .. } => slice.clone(),
+        }
+    }
+
+    pub fn scan(&self, clauses: &[AnyClause]) -> TableSlice {
+        self.index_scan(clauses).unwrap_or_else(|| self.to_slice())
+    }
+
+    fn index_scan(&self, clauses: &[AnyClause]) -> Option<TableSlice> {
+        match self {
+            SelectSource::Table(table) => table.index_scan(&Self::conditions(clauses)),
+            _ => None,
+        }
+    }
+
+    // Conditions that hold for every row of the result: the WHERE clauses up to the first LIMIT,
+    // since filtering before a LIMIT would change which rows it keeps.
+    fn conditions(clauses: &[AnyClause]) -> Vec<&AnyEvaluable> {
+        clauses
+            .iter()
+            .take_while(|clause| !matches!(clause, AnyClause::Limit(_)))
+            .filter_map(|clause| match clause {
+                AnyClause::Where(clause) => Some(&clause.evaluation),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn fields(&self) -> &HashMap<String, FieldType> {
+        match self {
+            SelectSource::Table(table) => table.fields(),
+            SelectSource::Catalog { fields, .. } | SelectSource::View { fields, .. } => fields,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            SelectSource::Table(table) => table.len(),
+            SelectSource::Catalog { slice, .. } | SelectSource::View { slice, .. } => slice.records.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn scan_kind(&self, clauses: &[AnyClause]) -> &'static str {
+        match self {
+            SelectSource::Table(table) if table.uses_index(&Self::conditions(clauses)) => "index scan",
+            SelectSource::Table(_) => "full scan",
+            SelectSource::Catalog { .. } => "catalog scan",
+            SelectSource::View { .. } => "view scan",
+        }
+    }
+}
@@ -1,49 +1,281 @@
-﻿use std::collections::HashMap;
-use crate::database::key::DatabaseKey;
+﻿use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use crate::commands::clauses::evaluable::{AnyEvaluable, Comparison, Evaluable};
+use crate::database::constraint::Constraint;
+use crate::database::index::Index;
+use crate::database::key::{DatabaseKey, TableKey};
 use crate::database::record::{IntermediateRecord, Record};
 use crate::database::slice::TableSlice;
 use crate::database::types::FieldType;
-use crate::database::value::Value;
+use crate::database::value::{IntermediateValue, KeyValue, Value};
 use crate::errors::Error;
 
+#[derive(Clone, Debug)]
+pub enum OnConflict {
+    Fail,
+    Ignore,
+    Replace,
+    Update(Vec<(String, IntermediateValue)>),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InsertSummary {
+    pub inserted: usize,
+    pub replaced: usize,
+    pub updated: usize,
+    pub skipped: usize,
+    pub assigned_keys: Vec<i64>,
+}
+
+impl Display for InsertSummary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Inserted {}, replaced {}, updated {} and skipped {} records", self.inserted, self.replaced, self.updated, self.skipped)?;
+
+        if !self.assigned_keys.is_empty() {
+            let keys = self.assigned_keys.iter().map(|key| key.to_string()).collect::<Vec<_>>().join(", ");
+            write!(f, " (assigned keys: {})", keys)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct StagedRecords<K: DatabaseKey> {
+    pub summary: InsertSummary,
+    pub records: Vec<(TableKey<K>, Record)>,
+    next_key: Option<i64>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Table<K: DatabaseKey> {
-    key: String,
+    key: Vec<String>,
     fields: HashMap<String, FieldType>,
-    records: HashMap<K, Record>,
+    nullable: HashSet<String>,
+    records: HashMap<TableKey<K>, Record>,
+    next_key: Option<i64>,
+    constraints: Vec<Constraint>,
+    indexes: HashMap<String, Index<K>>,
+    unique_values: HashMap<String, Index<K>>,
 }
 
 impl<K: DatabaseKey> Table<K> {
-    pub fn new(key: String, fields: HashMap<String, FieldType>, records: HashMap<K, Record>) -> Self {
-        Table { key, fields, records }
+    pub fn new(key: Vec<String>, fields: HashMap<String, FieldType>, records: HashMap<TableKey<K>, Record>) -> Result<Self, Error> {
+        for (index, field) in key.iter().enumerate() {
+            if key[..index].contains(field) {
+                return Err(Error::TypeError(format!("Key field '{}' is listed more than once", field)));
+            }
+        }
+
+        let table = Table { key, fields, nullable: HashSet::new(), records, next_key: None, constraints: Vec::new(), indexes: HashMap::new(), unique_values: HashMap::new() };
+        table.key_types()?;
+
+        Ok(table)
+    }
+
+    pub fn enable_auto_increment(&mut self) -> Result<(), Error> {
+        if self.key_types()? != [FieldType::Int] {
+            return Err(Error::TypeError(format!("AUTO needs a single Int key but the key is ({})", self.key_name())));
+        }
+
+        self.next_key = Some(1);
+        Ok(())
+    }
+
+    pub fn set_nullable(&mut self, field: &String) -> Result<(), Error> {
+        if !self.fields.contains_key(field) {
+            return Err(Error::MissingFieldError(format!("Field '{}' does not exist", field)));
+        }
+
+        if self.is_key(field) {
+            return Err(Error::TypeError(format!("Key field '{}' cannot be NULL", field)));
+        }
+
+        self.nullable.insert(field.clone());
+        Ok(())
+    }
+
+    pub fn is_nullable(&self, field: &String) -> bool {
+        self.nullable.contains(field)
+    }
+
+    pub fn key(&self) -> &Vec<String> {
+        &self.key
+    }
+
+    pub fn add_constraint(&mut self, constraint: Constraint) -> Result<(), Error> {
+        if let Some(field) = constraint.fields().into_iter().find(|field| !self.fields.contains_key(*field)) {
+            return Err(Error::MissingFieldError(format!("Constraint {} uses unknown field '{}'", constraint, field)));
+        }
+
+        // UNIQUE keeps its own set of values, separate from the indexes made by CREATE INDEX.
+        if let Constraint::Unique { field } = &constraint {
+            if !Index::<K>::supports(&self.fields[field]) {
+                return Err(Error::TypeError(format!("Fields of type {} cannot be UNIQUE", self.fields[field])));
+            }
+
+            self.unique_values.insert(field.clone(), self.build_index(field));
+        }
+
+        self.constraints.push(constraint);
+        Ok(())
+    }
+
+    pub fn constraints(&self) -> &Vec<Constraint> {
+        &self.constraints
+    }
+
+    pub fn add_index(&mut self, field: &String) -> Result<(), Error> {
+        let field_type = self.fields.get(field)
+            .ok_or_else(|| Error::MissingFieldError(format!("Field '{}' does not exist", field)))?;
+
+        if !Index::<K>::supports(field_type) {
+            return Err(Error::TypeError(format!("Fields of type {} cannot be indexed", field_type)));
+        }
+
+        if self.indexes.contains_key(field) {
+            return Err(Error::AlreadyExistsError(format!("Field '{}' is already indexed", field)));
+        }
+
+        self.indexes.insert(field.clone(), self.build_index(field));
+        Ok(())
+    }
+
+    fn build_index(&self, field: &String) -> Index<K> {
+        let mut index = Index::new();
+
+        for (key, record) in &self.records {
+            if let Some(value) = record.values.get(field) {
+                index.insert(value, key);
+            }
+        }
+
+        index
+    }
+
+    pub fn indexes(&self) -> &HashMap<String, Index<K>> {
+        &self.indexes
+    }
+
+    fn index_record(indexes: &mut HashMap<String, Index<K>>, key: &TableKey<K>, record: &Record, insert: bool) {
+        for (field, index) in indexes {
+            if let Some(value) = record.values.get(field) {
+                match insert {
+                    true => index.insert(value, key),
+                    false => index.remove(value, key),
+                }
+            }
+        }
+    }
+
+    fn index_lookup(&self, comparison: &Comparison) -> Option<BTreeSet<&TableKey<K>>> {
+        if comparison.function.is_some() {
+            return None;
+        }
+
+        let index = self.indexes.get(&comparison.field)?;
+        let value = comparison.constant.to_value(self.fields.get(&comparison.field)?).ok()?;
+
+        if let Value::Null = value {
+            return None;
+        }
+
+        index.lookup(&comparison.op, &value)
+    }
+
+    fn index_candidates(&self, evaluations: &[&AnyEvaluable]) -> Option<BTreeSet<&TableKey<K>>> {
+        evaluations
+            .iter()
+            .filter_map(|evaluation| evaluation.candidates(&|comparison| self.index_lookup(comparison)))
+            .reduce(|mut candidates, other| {
+                candidates.retain(|key| other.contains(key));
+                candidates
+            })
+    }
+
+    pub fn uses_index(&self, evaluations: &[&AnyEvaluable]) -> bool {
+        self.index_candidates(evaluations).is_some()
     }
 
-    pub fn key_type(&self) -> Result<FieldType, Error> {
-        self.fields.get(&self.key)
-            .ok_or_else(|| Error::NotSpecifiedError("Field type of key was not specified".to_string()))
+    pub fn index_scan(&self, evaluations: &[&AnyEvaluable]) -> Option<TableSlice> {
+        let keys = self.index_candidates(evaluations)?;
+
+        let records = keys
+            .into_iter()
+            .filter_map(|key| self.records.get(key))
             .cloned()
+            .collect();
+
+        Some(TableSlice::new(records))
     }
 
-    fn validate_new_key(&self, key_value: &Value, typed_key: &K) -> Result<(), Error> {
-        if key_value.get_field_type() != K::get_field_type() {
-            return Err(Error::TypeError("Mismatched field type".to_string()));
+    pub fn key_name(&self) -> String {
+        self.key.join(", ")
+    }
+
+    pub fn is_key(&self, field: &String) -> bool {
+        self.key.contains(field)
+    }
+
+    pub fn fields(&self) -> &HashMap<String, FieldType> {
+        &self.fields
+    }
+
+    pub fn key_types(&self) -> Result<Vec<FieldType>, Error> {
+        if self.key.is_empty() {
+            return Err(Error::NotSpecifiedError("Key was not specified".to_string()));
         }
 
-        if self.records.contains_key(typed_key) {
-            return Err(Error::AlreadyExistsError("Key already exists".to_string()));
+        self.key
+            .iter()
+            .map(|key| self.fields.get(key)
+                .ok_or_else(|| Error::NotSpecifiedError(format!("Field type of key '{}' was not specified", key)))
+                .cloned())
+            .collect()
+    }
+
+    pub fn typed_key(&self, values: &[KeyValue]) -> Result<TableKey<K>, Error> {
+        if values.len() != self.key.len() {
+            return Err(Error::TypeError(format!("Key ({}) has {} fields but {} values were given", self.key_name(), self.key.len(), values.len())));
+        }
+
+        values
+            .iter()
+            .zip(&self.key)
+            .map(|(value, key)| {
+                let field_type = self.fields.get(key)
+                    .ok_or_else(|| Error::NotSpecifiedError(format!("Field type of key '{}' was not specified", key)))?;
+
+                K::from_value(&IntermediateValue::from(value).to_value(field_type)?)
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(TableKey)
+    }
+
+    fn validate_key_type(&self, key_value: &Value) -> Result<(), Error> {
+        if !key_value.get_field_type().is_some_and(|field_type| K::supports(&field_type)) {
+            return Err(Error::TypeError("Mismatched field type".to_string()));
         }
 
         Ok(())
     }
 
+    fn field_value(&self, field_name: &String, field_type: &FieldType, intermediate_value: &IntermediateValue) -> Result<Value, Error> {
+        match intermediate_value.to_value(field_type)? {
+            Value::Null if !self.is_nullable(field_name) => Err(Error::TypeError(format!("Field '{}' cannot be NULL", field_name))),
+            value => Ok(value),
+        }
+    }
+
     fn convert_record(&self, intermediate: IntermediateRecord) -> Result<Record, Error> {
         let mut values = HashMap::new();
 
         for (field_name, field_type) in &self.fields {
-            let intermediate_value = intermediate.values.get(field_name)
-                .ok_or_else(|| Error::MissingFieldError(format!("Record missing required field '{}'", field_name)))?;
+            let value = match intermediate.values.get(field_name) {
+                Some(intermediate_value) => self.field_value(field_name, field_type, intermediate_value)?,
+                None if self.is_nullable(field_name) => Value::Null,
+                None => return Err(Error::MissingFieldError(format!("Record missing required field '{}'", field_name))),
+            };
 
-            let value = intermediate_value.to_value(field_type)?;
             values.insert(field_name.clone(), value);
         }
 
@@ -56,25 +288,212 @@ impl<K: DatabaseKey> Table<K> {
         Ok(Record { values })
     }
 
-    pub fn add_record(&mut self, record: IntermediateRecord) -> Result<(), Error> {
+    fn prepare_record(&self, record: IntermediateRecord) -> Result<(TableKey<K>, Record), Error> {
         let record = self.convert_record(record)?;
 
-        let key_value = record.values.get(&self.key)
-            .ok_or_else(|| Error::MissingFieldError("The record is missing the key value".to_string()))?;
+        let mut typed_key = Vec::new();
+
+        for key in &self.key {
+            let key_value = record.values.get(key)
+                .ok_or_else(|| Error::MissingFieldError("The record is missing the key value".to_string()))?;
+
+            self.validate_key_type(key_value)?;
+            typed_key.push(K::from_value(key_value)?);
+        }
+
+        Ok((TableKey(typed_key), record))
+    }
+
+    fn check_constraints(&self, key: &TableKey<K>, record: &Record, staged: &HashMap<TableKey<K>, usize>, staged_indexes: &HashMap<String, Index<K>>) -> Result<(), Error> {
+        for constraint in &self.constraints {
+            let satisfied = match constraint {
+                Constraint::Unique { field } => {
+                    let Some(value) = record.values.get(field).filter(|value| !matches!(value, Value::Null)) else { continue };
+
+                    // Committed records that are staged again are checked with their staged values.
+                    let committed = self.unique_values.get(field)
+                        .into_iter()
+                        .flat_map(|index| index.matching(value))
+                        .any(|other| other != key && !staged.contains_key(other));
+                    let pending = staged_indexes.get(field)
+                        .into_iter()
+                        .flat_map(|index| index.matching(value))
+                        .any(|other| other != key);
+
+                    !committed && !pending
+                },
+                Constraint::Check { condition, .. } => condition.evaluate(record)?,
+                // References need the other tables and are checked by the database.
+                Constraint::References { .. } => true,
+            };
+
+            if !satisfied {
+                return Err(Error::ConstraintViolationError(format!("Record with key '{}' violates constraint {}", key, constraint)));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update_record(&self, current: &Record, assignments: &[(String, IntermediateValue)]) -> Result<Record, Error> {
+        let mut updated = current.clone();
+
+        for (field_name, intermediate_value) in assignments {
+            if self.is_key(field_name) {
+                return Err(Error::TypeError(format!("Key field '{}' cannot be updated", field_name)));
+            }
+
+            let field_type = self.fields.get(field_name)
+                .ok_or_else(|| Error::NotExistError(format!("Unexpected field '{}'", field_name)))?;
 
-        let typed_key = K::from_value(key_value)?;
+            let value = self.field_value(field_name, field_type, intermediate_value)?;
+            updated.values.insert(field_name.clone(), value);
+        }
 
-        self.validate_new_key(key_value, &typed_key)?;
+        Ok(updated)
+    }
 
-        self.records.insert(typed_key, record);
+    pub fn add_record(&mut self, record: IntermediateRecord) -> Result<(), Error> {
+        self.add_records(vec![record], &OnConflict::Fail)?;
 
         Ok(())
     }
 
-    pub fn delete_record(&mut self, record_key: &K) -> Result<(), Error> {
-        _ = self.records.remove(record_key)
+    pub fn add_records(&mut self, records: Vec<IntermediateRecord>, on_conflict: &OnConflict) -> Result<(InsertSummary, Vec<Record>), Error> {
+        let staged = self.stage_records(records, on_conflict)?;
+
+        Ok(self.commit_records(staged))
+    }
+
+    pub fn stage_records(&self, records: Vec<IntermediateRecord>, on_conflict: &OnConflict) -> Result<StagedRecords<K>, Error> {
+        let mut summary = InsertSummary::default();
+        let mut staged: Vec<(TableKey<K>, Record)> = Vec::new();
+        let mut positions: HashMap<TableKey<K>, usize> = HashMap::new();
+        let mut next_key = self.next_key;
+
+        let mut staged_indexes = self.constraints
+            .iter()
+            .filter_map(|constraint| match constraint {
+                Constraint::Unique { field } => Some((field.clone(), Index::new())),
+                _ => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        for mut record in records {
+            if let Some(next) = next_key && !record.values.contains_key(&self.key[0]) {
+                record.values.insert(self.key[0].clone(), IntermediateValue::Int(next));
+                summary.assigned_keys.push(next);
+            }
+
+            let (typed_key, record) = self.prepare_record(record)?;
+
+            if let (Some(next), Some(Value::Int(key))) = (next_key.as_mut(), record.values.get(&self.key[0])) {
+                let after = key.checked_add(1)
+                    .ok_or_else(|| Error::ConstraintViolationError(format!("AUTO key '{}' has no values left after {}", self.key[0], key)))?;
+                *next = (*next).max(after);
+            }
+
+            let staged_index = positions.get(&typed_key).copied();
+            let current = match staged_index {
+                Some(index) => Some(&staged[index].1),
+                None => self.records.get(&typed_key),
+            };
+
+            let record = match (current, on_conflict) {
+                (None, _) => {
+                    summary.inserted += 1;
+                    record
+                },
+                (Some(_), OnConflict::Fail) => {
+                    return Err(Error::AlreadyExistsError(format!("Key '{}' already exists", typed_key)));
+                },
+                (Some(_), OnConflict::Ignore) => {
+                    summary.skipped += 1;
+                    continue;
+                },
+                (Some(_), OnConflict::Replace) => {
+                    summary.replaced += 1;
+                    record
+                },
+                (Some(current), OnConflict::Update(assignments)) => {
+                    summary.updated += 1;
+                    self.update_record(current, assignments)?
+                },
+            };
+
+            self.check_constraints(&typed_key, &record, &positions, &staged_indexes)?;
+
+            if let Some(index) = staged_index {
+                Self::index_record(&mut staged_indexes, &typed_key, &staged[index].1, false);
+            }
+            Self::index_record(&mut staged_indexes, &typed_key, &record, true);
+
+            match staged_index {
+                Some(index) => staged[index].1 = record,
+                None => {
+                    positions.insert(typed_key.clone(), staged.len());
+                    staged.push((typed_key, record));
+                },
+            }
+        }
+
+        Ok(StagedRecords { summary, records: staged, next_key })
+    }
+
+    pub fn commit_records(&mut self, staged: StagedRecords<K>) -> (InsertSummary, Vec<Record>) {
+        let written = staged.records.iter().map(|(_, record)| record.clone()).collect();
+
+        for (key, record) in &staged.records {
+            if let Some(current) = self.records.get(key) {
+                Self::index_record(&mut self.indexes, key, current, false);
+                Self::index_record(&mut self.unique_values, key, current, false);
+            }
+
+            Self::index_record(&mut self.indexes, key, record, true);
+            Self::index_record(&mut self.unique_values, key, record, true);
+        }
+
+        self.records.extend(staged.records);
+        self.next_key = staged.next_key;
+
+        (staged.summary, written)
+    }
+
+    pub fn delete_record(&mut self, record_key: &TableKey<K>) -> Result<Record, Error> {
+        let record = self.records.remove(record_key)
+            .ok_or_else(|| Error::NotExistError(format!("Table missing a record with key '{}'", record_key)))?;
+
+        Self::index_record(&mut self.indexes, record_key, &record, false);
+        Self::index_record(&mut self.unique_values, record_key, &record, false);
+
+        Ok(record)
+    }
+
+    pub fn set_null(&mut self, record_key: &TableKey<K>, field: &str) -> Result<(), Error> {
+        let mut record = self.records.remove(record_key)
             .ok_or_else(|| Error::NotExistError(format!("Table missing a record with key '{}'", record_key)))?;
 
+        Self::index_record(&mut self.indexes, record_key, &record, false);
+        Self::index_record(&mut self.unique_values, record_key, &record, false);
+        record.values.insert(field.to_string(), Value::Null);
+        Self::index_record(&mut self.indexes, record_key, &record, true);
+        Self::index_record(&mut self.unique_values, record_key, &record, true);
+
+        self.records.insert(record_key.clone(), record);
+        Ok(())
+    }
+
+    pub fn records(&self) -> &HashMap<TableKey<K>, Record> {
+        &self.records
+    }
+
+    pub fn validate_fields(&self, fields: &[String]) -> Result<(), Error> {
+        for field in fields {
+            if !self.fields.contains_key(field) {
+                return Err(Error::MissingFieldError(format!("Field '{}' does not exist", field)));
+            }
+        }
+
         Ok(())
     }
     
@@ -0,0 +1,228 @@
+﻿use std::fmt::{Display, Formatter};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::database::value::{IntermediateValue, Value};
+use crate::errors::Error;
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DatePart {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl DatePart {
+    pub fn extract(&self, value: &Value) -> Result<i64, Error> {
+        let seconds = match value {
+            Value::Date(days) => days * SECONDS_PER_DAY,
+            Value::Timestamp(seconds) => *seconds,
+            other => return Err(Error::TypeError(format!("Cannot extract {} from {:?}", self, other))),
+        };
+
+        let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+        let time = seconds.rem_euclid(SECONDS_PER_DAY);
+
+        let part = match self {
+            DatePart::Year => year,
+            DatePart::Month => month,
+            DatePart::Day => day,
+            DatePart::Hour => time / 3600,
+            DatePart::Minute => time % 3600 / 60,
+            DatePart::Second => time % 60,
+        };
+
+        Ok(part)
+    }
+}
+
+impl Display for DatePart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let part = match self {
+            DatePart::Year => "YEAR",
+            DatePart::Month => "MONTH",
+            DatePart::Day => "DAY",
+            DatePart::Hour => "HOUR",
+            DatePart::Minute => "MINUTE",
+            DatePart::Second => "SECOND",
+        };
+
+        write!(f, "{}", part)
+    }
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+pub fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+pub fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+fn parse_number(part: &str, digits: usize, input: &str) -> Result<i64, Error> {
+    if part.len() != digits || !part.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Error::ParseError(format!("Invalid date or time '{}'", input)));
+    }
+
+    part.parse::<i64>()
+        .map_err(|e| Error::ParseError(format!("Invalid date or time '{}': {}", input, e)))
+}
+
+pub fn parse_date(input: &str) -> Result<i64, Error> {
+    let parts = input.split('-').collect::<Vec<_>>();
+    if parts.len() != 3 {
+        return Err(Error::ParseError(format!("Expected a date like 2024-01-31 but got '{}'", input)));
+    }
+
+    let year = parse_number(parts[0], 4, input)?;
+    let month = parse_number(parts[1], 2, input)?;
+    let day = parse_number(parts[2], 2, input)?;
+
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) {
+        return Err(Error::ParseError(format!("Date '{}' does not exist", input)));
+    }
+
+    Ok(days_from_civil(year, month, day))
+}
+
+pub fn parse_timestamp(input: &str) -> Result<i64, Error> {
+    let (date, time) = input.split_once(['T', ' '])
+        .ok_or_else(|| Error::ParseError(format!("Expected a timestamp like 2024-01-31 12:30:00 but got '{}'", input)))?;
+
+    let parts = time.split(':').collect::<Vec<_>>();
+    if parts.len() != 3 {
+        return Err(Error::ParseError(format!("Expected a timestamp like 2024-01-31 12:30:00 but got '{}'", input)));
+    }
+
+    let hour = parse_number(parts[0], 2, input)?;
+    let minute = parse_number(parts[1], 2, input)?;
+    let second = parse_number(parts[2], 2, input)?;
+
+    if hour > 23 || minute > 59 || second > 59 {
+        return Err(Error::ParseError(format!("Time '{}' does not exist", time)));
+    }
+
+    Ok(parse_date(date)? * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second)
+}
+
+pub fn format_date(days: i64) -> String {
+    let (year, month, day) = civil_from_days(days);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+pub fn format_timestamp(seconds: i64) -> String {
+    let time = seconds.rem_euclid(SECONDS_PER_DAY);
+    format!("{} {:02}:{:02}:{:02}", format_date(seconds.div_euclid(SECONDS_PER_DAY)), time / 3600, time % 3600 / 60, time % 60)
+}
+
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+pub fn add_seconds(value: IntermediateValue, seconds: i64) -> Option<IntermediateValue> {
+    match value {
+        IntermediateValue::Date(days) if seconds % SECONDS_PER_DAY == 0 =>
+            days.checked_add(seconds / SECONDS_PER_DAY).map(IntermediateValue::Date),
+        IntermediateValue::Date(days) =>
+            days.checked_mul(SECONDS_PER_DAY).and_then(|start| start.checked_add(seconds)).map(IntermediateValue::Timestamp),
+        IntermediateValue::Timestamp(timestamp) =>
+            timestamp.checked_add(seconds).map(IntermediateValue::Timestamp),
+        other => Some(other),
+    }
+}
+
+// NOW() and TODAY() are kept unresolved until a statement runs, so a prepared statement
+// reads the clock on every execution and the session log can record the resolved literal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CurrentTime {
+    pub date: bool,
+    pub intervals: Vec<i64>,
+}
+
+impl CurrentTime {
+    pub fn resolve(&self, now: i64) -> Result<IntermediateValue, Error> {
+        let start = match self.date {
+            true => IntermediateValue::Date(now.div_euclid(SECONDS_PER_DAY)),
+            false => IntermediateValue::Timestamp(now),
+        };
+
+        self.intervals
+            .iter()
+            .try_fold(start, |value, seconds| add_seconds(value, *seconds))
+            .ok_or_else(|| Error::TypeError(format!("Date arithmetic overflows in {}", self)))
+    }
+}
+
+impl Display for CurrentTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", if self.date { "TODAY()" } else { "NOW()" })?;
+
+        for seconds in &self.intervals {
+            write!(f, " {} {} SECONDS", if *seconds < 0 { "-" } else { "+" }, seconds.unsigned_abs())?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_format() {
+        assert_eq!(parse_date("1970-01-01").unwrap(), 0);
+        assert_eq!(parse_date("2000-03-01").unwrap(), 11_017);
+        assert_eq!(format_date(parse_date("2024-02-29").unwrap()), "2024-02-29");
+        assert_eq!(format_date(parse_date("1969-12-31").unwrap()), "1969-12-31");
+        assert!(parse_date("2023-02-29").is_err());
+        assert!(parse_date("2023-1-02").is_err());
+
+        let timestamp = parse_timestamp("2024-01-31T12:30:05").unwrap();
+        assert_eq!(format_timestamp(timestamp), "2024-01-31 12:30:05");
+        assert!(parse_timestamp("2024-01-31 24:00:00").is_err());
+    }
+
+    #[test]
+    fn test_extract() {
+        let value = Value::Timestamp(parse_timestamp("2024-01-31 12:30:05").unwrap());
+
+        assert_eq!(DatePart::Year.extract(&value).unwrap(), 2024);
+        assert_eq!(DatePart::Month.extract(&value).unwrap(), 1);
+        assert_eq!(DatePart::Day.extract(&value).unwrap(), 31);
+        assert_eq!(DatePart::Minute.extract(&value).unwrap(), 30);
+        assert_eq!(DatePart::Hour.extract(&Value::Date(0)).unwrap(), 0);
+        assert!(DatePart::Year.extract(&Value::Int(2024)).is_err());
+    }
+}
@@ -1,8 +1,6 @@
-﻿#[derive(Clone, Debug, PartialEq, Eq)]
-pub enum KeyType {
-    String,
-    Int,
-}
+﻿use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use crate::database::value::escape_string;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum FieldType {
@@ -10,4 +8,31 @@ pub enum FieldType {
     String,
     Int,
     Float,
+    Date,
+    Timestamp,
+    Decimal(u32, u32),
+    Bytes,
+    Uuid,
+    List(Box<FieldType>),
+    Json,
+    Enum(Arc<[String]>),
+}
+
+impl Display for FieldType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FieldType::Bool => write!(f, "Bool"),
+            FieldType::String => write!(f, "String"),
+            FieldType::Int => write!(f, "Int"),
+            FieldType::Float => write!(f, "Float"),
+            FieldType::Date => write!(f, "Date"),
+            FieldType::Timestamp => write!(f, "Timestamp"),
+            FieldType::Decimal(precision, scale) => write!(f, "Decimal({}, {})", precision, scale),
+            FieldType::Bytes => write!(f, "Bytes"),
+            FieldType::Uuid => write!(f, "Uuid"),
+            FieldType::List(item) => write!(f, "List<{}>", item),
+            FieldType::Json => write!(f, "Json"),
+            FieldType::Enum(variants) => write!(f, "Enum({})", variants.iter().map(|variant| format!("\"{}\"", escape_string(variant))).collect::<Vec<_>>().join(", ")),
+        }
+    }
 }
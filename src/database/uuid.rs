@@ -0,0 +1,57 @@
+﻿use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use crate::database::hex::{format_hex, parse_hex};
+use crate::errors::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Uuid([u8; 16]);
+
+impl Uuid {
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl FromStr for Uuid {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let groups = input.split('-').map(str::len).collect::<Vec<_>>();
+        if groups != [8, 4, 4, 4, 12] {
+            return Err(Error::TypeError(format!("'{}' is not a valid UUID", input)));
+        }
+
+        if let Some(c) = input.chars().find(|c| *c != '-' && !c.is_ascii_hexdigit()) {
+            return Err(Error::TypeError(format!("'{}' is not a valid UUID: '{}' is not a hex digit", input, c)));
+        }
+
+        let bytes = parse_hex(&input.replace('-', ""))?;
+
+        let mut uuid = [0u8; 16];
+        uuid.copy_from_slice(&bytes);
+
+        Ok(Self(uuid))
+    }
+}
+
+impl Display for Uuid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let hex = format_hex(&self.0);
+        write!(f, "{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let uuid = "123E4567-e89b-12d3-a456-426614174000".parse::<Uuid>().unwrap();
+        assert_eq!(uuid.to_string(), "123e4567-e89b-12d3-a456-426614174000");
+        assert_eq!(uuid.as_bytes()[0], 0x12);
+
+        assert!("123e4567e89b12d3a456426614174000".parse::<Uuid>().is_err());
+        assert!(matches!("123e4567-e89b-12d3-a456-42661417400g".parse::<Uuid>(), Err(Error::TypeError(message)) if message.ends_with("'g' is not a hex digit")));
+    }
+}
@@ -1,6 +1,12 @@
 ﻿use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use crate::database::decimal::Decimal;
+use crate::database::hex::format_hex;
+use crate::database::json::Json;
+use crate::database::temporal::{format_date, format_timestamp, CurrentTime, SECONDS_PER_DAY};
 use crate::database::types::FieldType;
+use crate::database::uuid::Uuid;
 use crate::errors::Error;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -9,20 +15,95 @@ pub enum KeyValue {
     Int(i64),
 }
 
+impl TryFrom<&IntermediateValue> for KeyValue {
+    type Error = Error;
+
+    fn try_from(value: &IntermediateValue) -> Result<Self, Self::Error> {
+        match value {
+            IntermediateValue::String(s) => Ok(KeyValue::String(s.clone())),
+            IntermediateValue::Int(i) => Ok(KeyValue::Int(*i)),
+            IntermediateValue::Uuid(u) => Ok(KeyValue::String(u.to_string())),
+            IntermediateValue::Numeric(n) if *n == (*n as i64 as f64) => Ok(KeyValue::Int(*n as i64)),
+            IntermediateValue::Decimal(d) => d.to_i64()
+                .map(KeyValue::Int)
+                .ok_or_else(|| Error::TypeError(format!("{} cannot be used as a key", d))),
+            IntermediateValue::Parameter(n) => Err(Error::NotSpecifiedError(format!("Parameter ${} is not bound", n))),
+            other => Err(Error::TypeError(format!("{:?} cannot be used as a key", other))),
+        }
+    }
+}
+
+impl From<&KeyValue> for IntermediateValue {
+    fn from(value: &KeyValue) -> Self {
+        match value {
+            KeyValue::String(s) => IntermediateValue::String(s.clone()),
+            KeyValue::Int(i) => IntermediateValue::Int(*i),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum IntermediateValue {
     Bool(bool),
     String(String),
+    Int(i64),
     Numeric(f64),
+    Decimal(Decimal),
+    Date(i64),
+    Timestamp(i64),
+    Bytes(Vec<u8>),
+    Uuid(Uuid),
+    List(Vec<IntermediateValue>),
+    Json(Json),
+    Null,
+    Current(CurrentTime),
+    Parameter(usize),
+}
+
+impl Display for IntermediateValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntermediateValue::Bool(b) => write!(f, "{}", b),
+            IntermediateValue::String(s) => write!(f, "\"{}\"", escape_string(s)),
+            IntermediateValue::Int(i) => write!(f, "{}", i),
+            IntermediateValue::Numeric(n) => write!(f, "{}", n),
+            IntermediateValue::Decimal(d) => write!(f, "{}", d),
+            IntermediateValue::Date(d) => write!(f, "DATE \"{}\"", format_date(*d)),
+            IntermediateValue::Timestamp(t) => write!(f, "TIMESTAMP \"{}\"", format_timestamp(*t)),
+            IntermediateValue::Bytes(b) => write!(f, "x\"{}\"", format_hex(b)),
+            IntermediateValue::Uuid(u) => write!(f, "\"{}\"", u),
+            IntermediateValue::List(items) => write!(f, "[{}]", items.iter().map(|item| item.to_string()).collect::<Vec<_>>().join(", ")),
+            IntermediateValue::Json(j) => write!(f, "JSON '{}'", j),
+            IntermediateValue::Null => write!(f, "NULL"),
+            IntermediateValue::Current(c) => write!(f, "{}", c),
+            IntermediateValue::Parameter(n) => write!(f, "${}", n),
+        }
+    }
 }
 
 impl IntermediateValue {
+    pub fn resolve_current_time(&mut self, now: i64) -> Result<(), Error> {
+        match self {
+            IntermediateValue::Current(current) => *self = current.resolve(now)?,
+            IntermediateValue::List(items) => {
+                for item in items {
+                    item.resolve_current_time(now)?;
+                }
+            },
+            _ => {},
+        }
+
+        Ok(())
+    }
+
     pub fn to_value(&self, field_type: &FieldType) -> Result<Value, Error> {
         match (field_type, self) {
             (FieldType::Bool, IntermediateValue::Bool(b)) =>
                 Ok(Value::Bool(*b)),
             (FieldType::String, IntermediateValue::String(s)) =>
                 Ok(Value::String(s.clone())),
+            (FieldType::Int, IntermediateValue::Int(i)) =>
+                Ok(Value::Int(*i)),
             (FieldType::Int, IntermediateValue::Numeric(n)) => {
                 if *n == (*n as i64 as f64) {
                     Ok(Value::Int(*n as i64))
@@ -30,29 +111,134 @@ impl IntermediateValue {
                     Err(Error::TypeError(format!("Expected integer but got non-integer number '{}'", n)))
                 }
             }
+            (FieldType::Int, IntermediateValue::Decimal(d)) =>
+                d.to_i64()
+                    .map(Value::Int)
+                    .ok_or_else(|| Error::TypeError(format!("Expected integer but got non-integer number '{}'", d))),
+            (FieldType::Float, IntermediateValue::Int(i)) =>
+                Ok(Value::Float(*i as f64)),
             (FieldType::Float, IntermediateValue::Numeric(n)) =>
                 Ok(Value::Float(*n)),
+            (FieldType::Float, IntermediateValue::Decimal(d)) =>
+                Ok(Value::Float(d.to_f64())),
+            (FieldType::Decimal(precision, scale), IntermediateValue::Decimal(d)) =>
+                Ok(Value::Decimal(d.fit(*precision, *scale)?)),
+            (FieldType::Decimal(precision, scale), IntermediateValue::Int(i)) =>
+                Ok(Value::Decimal(Decimal::from(*i).fit(*precision, *scale)?)),
+            (FieldType::Decimal(precision, scale), IntermediateValue::Numeric(n)) if *n == (*n as i64 as f64) =>
+                Ok(Value::Decimal(Decimal::from(*n as i64).fit(*precision, *scale)?)),
+            (FieldType::Date, IntermediateValue::Date(d)) =>
+                Ok(Value::Date(*d)),
+            (FieldType::Timestamp, IntermediateValue::Timestamp(t)) =>
+                Ok(Value::Timestamp(*t)),
+            (FieldType::Timestamp, IntermediateValue::Date(d)) =>
+                Ok(Value::Timestamp(d * SECONDS_PER_DAY)),
+            (FieldType::Bytes, IntermediateValue::Bytes(b)) =>
+                Ok(Value::Bytes(b.clone())),
+            (FieldType::Uuid, IntermediateValue::Uuid(u)) =>
+                Ok(Value::Uuid(*u)),
+            (FieldType::Uuid, IntermediateValue::String(s)) =>
+                Ok(Value::Uuid(s.parse()?)),
+            (FieldType::List(item_type), IntermediateValue::List(items)) => {
+                let values = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, item)| item.to_value(item_type)
+                        .map_err(|e| Error::TypeError(format!("List element {} is invalid: {}", index, e))))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Value::List(values))
+            },
+            (FieldType::Enum(variants), IntermediateValue::String(s)) => variants
+                .iter()
+                .position(|variant| variant == s)
+                .map(|index| Value::Enum(index, variants.clone()))
+                .ok_or_else(|| Error::TypeError(format!("'{}' is not one of {}", s, field_type))),
+            (FieldType::Json, IntermediateValue::Json(j)) =>
+                Ok(Value::Json(j.clone())),
+            (FieldType::Json, IntermediateValue::String(s)) =>
+                Ok(Value::Json(s.parse()?)),
+            (FieldType::Json, IntermediateValue::Int(i)) =>
+                Ok(Value::Json(Json::Int(*i))),
+            (FieldType::Json, IntermediateValue::Bool(b)) =>
+                Ok(Value::Json(Json::Bool(*b))),
+            // Whether the field accepts NULL is up to the table.
+            (_, IntermediateValue::Null) =>
+                Ok(Value::Null),
+            (_, IntermediateValue::Parameter(n)) =>
+                Err(Error::NotSpecifiedError(format!("Parameter ${} is not bound", n))),
+            (_, IntermediateValue::Current(c)) =>
+                Err(Error::NotSpecifiedError(format!("{} is not resolved", c))),
             (expected, got) =>
                 Err(Error::TypeError(format!("Field expects {:?} but record provides {:?}", expected, got))),
         }
     }
 }
 
+impl From<&Value> for IntermediateValue {
+    fn from(value: &Value) -> Self {
+        match value {
+            Value::Bool(b) => IntermediateValue::Bool(*b),
+            Value::String(s) => IntermediateValue::String(s.clone()),
+            Value::Int(i) => IntermediateValue::Int(*i),
+            Value::Float(f) => IntermediateValue::Numeric(*f),
+            Value::Decimal(d) => IntermediateValue::Decimal(*d),
+            Value::Date(d) => IntermediateValue::Date(*d),
+            Value::Timestamp(t) => IntermediateValue::Timestamp(*t),
+            Value::Bytes(b) => IntermediateValue::Bytes(b.clone()),
+            Value::Uuid(u) => IntermediateValue::Uuid(*u),
+            Value::List(items) => IntermediateValue::List(items.iter().map(IntermediateValue::from).collect()),
+            Value::Json(j) => IntermediateValue::Json(j.clone()),
+            Value::Enum(index, variants) => IntermediateValue::String(variants[*index].clone()),
+            Value::Null => IntermediateValue::Null,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum Value {
     Bool(bool),
     String(String),
     Int(i64),
     Float(f64),
+    Decimal(Decimal),
+    Date(i64),
+    Timestamp(i64),
+    Bytes(Vec<u8>),
+    Uuid(Uuid),
+    List(Vec<Value>),
+    Json(Json),
+    Enum(usize, Arc<[String]>),
+    Null,
 }
 
 impl Value {
-    pub fn get_field_type(&self) -> FieldType {
-        match self {
+    pub fn get_field_type(&self) -> Option<FieldType> {
+        let field_type = match self {
             Value::Bool(_) => FieldType::Bool,
             Value::String(_) => FieldType::String,
             Value::Int(_) => FieldType::Int,
             Value::Float(_) => FieldType::Float,
+            Value::Decimal(d) => FieldType::Decimal(Decimal::MAX_PRECISION, d.scale()),
+            Value::Date(_) => FieldType::Date,
+            Value::Timestamp(_) => FieldType::Timestamp,
+            Value::Bytes(_) => FieldType::Bytes,
+            Value::Uuid(_) => FieldType::Uuid,
+            Value::List(items) => FieldType::List(Box::new(items.first().and_then(Value::get_field_type).unwrap_or(FieldType::String))),
+            Value::Json(_) => FieldType::Json,
+            Value::Enum(_, variants) => FieldType::Enum(variants.clone()),
+            Value::Null => return None,
+        };
+
+        Some(field_type)
+    }
+
+    pub fn json_path(&self, keys: &[String]) -> Result<Value, Error> {
+        match self {
+            Value::Json(json) => Ok(keys
+                .iter()
+                .try_fold(json, |node, key| node.get(key))
+                .map_or(Value::Json(Json::Null), Json::to_value)),
+            other => Err(Error::TypeError(format!("Cannot follow a JSON path into {:?}", other))),
         }
     }
 
@@ -62,6 +248,15 @@ impl Value {
             Value::String(_) => 1,
             Value::Int(_) => 2,
             Value::Float(_) => 3,
+            Value::Date(_) => 4,
+            Value::Timestamp(_) => 5,
+            Value::Decimal(_) => 6,
+            Value::Bytes(_) => 7,
+            Value::Uuid(_) => 8,
+            Value::List(_) => 9,
+            Value::Json(_) => 10,
+            Value::Enum(_, _) => 11,
+            Value::Null => 12,
         }
     }
 }
@@ -73,10 +268,37 @@ impl Display for Value {
             Value::String(s) => write!(f, "\"{}\"", s),
             Value::Int(i) => write!(f, "{}", i),
             Value::Float(fl) => write!(f, "{}", fl),
+            Value::Decimal(d) => write!(f, "{}", d),
+            Value::Date(d) => write!(f, "{}", format_date(*d)),
+            Value::Timestamp(t) => write!(f, "{}", format_timestamp(*t)),
+            Value::Bytes(b) => write!(f, "x\"{}\"", format_hex(b)),
+            Value::Uuid(u) => write!(f, "{}", u),
+            Value::List(items) => write!(f, "[{}]", items.iter().map(|item| item.to_string()).collect::<Vec<_>>().join(", ")),
+            Value::Json(j) => write!(f, "{}", j),
+            Value::Enum(index, variants) => write!(f, "\"{}\"", variants[*index]),
+            Value::Null => write!(f, "NULL"),
         }
     }
 }
 
+pub fn escape_string(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+pub fn unescape_string(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(chars.next_if(|next| matches!(next, '"' | '\\')).unwrap_or(c)),
+            _ => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
 pub fn sort_compare_values(a: &Option<&Value>, b: &Option<&Value>) -> Ordering {
     match (a, b) {
         (None, None) => Ordering::Equal,
@@ -86,16 +308,58 @@ pub fn sort_compare_values(a: &Option<&Value>, b: &Option<&Value>) -> Ordering {
         (Some(Value::String(x)), Some(Value::String(y))) => x.cmp(y),
         (Some(Value::Int(x)), Some(Value::Int(y))) => x.cmp(y),
         (Some(Value::Float(x)), Some(Value::Float(y))) => x.partial_cmp(y).unwrap_or(Ordering::Equal),
+        (Some(Value::Decimal(x)), Some(Value::Decimal(y))) => x.cmp(y),
+        (Some(Value::Date(x)), Some(Value::Date(y))) => x.cmp(y),
+        (Some(Value::Timestamp(x)), Some(Value::Timestamp(y))) => x.cmp(y),
+        (Some(Value::Bytes(x)), Some(Value::Bytes(y))) => x.cmp(y),
+        (Some(Value::Uuid(x)), Some(Value::Uuid(y))) => x.cmp(y),
+        (Some(Value::List(x)), Some(Value::List(y))) => x.iter()
+            .zip(y.iter())
+            .map(|(a, b)| sort_compare_values(&Some(a), &Some(b)))
+            .find(|ordering| *ordering != Ordering::Equal)
+            .unwrap_or_else(|| x.len().cmp(&y.len())),
+        (Some(Value::Json(x)), Some(Value::Json(y))) => x.compare(y),
+        (Some(Value::Enum(x, _)), Some(Value::Enum(y, _))) => x.cmp(y),
         (Some(va), Some(vb)) => va.value_order().cmp(&vb.value_order()),
     }
 }
 
 pub fn compare_value_intermediate_value(value: &Value, constant: &IntermediateValue) -> Result<Ordering, Error> {
     match (value, constant) {
-        (Value::Int(a), IntermediateValue::Numeric(b)) => Ok((*a as f64).partial_cmp(b).ok_or_else(|| Error::TypeError("".to_string()))?),
+        (Value::Int(a), IntermediateValue::Int(b)) => Ok(a.cmp(b)),
+        (Value::Int(a), IntermediateValue::Numeric(b)) if b.fract() == 0.0 && *b >= i64::MIN as f64 && *b < i64::MAX as f64 => Ok(a.cmp(&(*b as i64))),
+        (Value::Int(a), IntermediateValue::Numeric(b)) => Ok((*a as f64).partial_cmp(b).ok_or_else(|| Error::TypeError("numeric value".to_string()))?),
+        (Value::Float(a), IntermediateValue::Int(b)) => Ok(a.partial_cmp(&(*b as f64)).ok_or_else(|| Error::TypeError("numeric value".to_string()))?),
+        (Value::Decimal(a), IntermediateValue::Int(b)) => Ok(a.cmp(&Decimal::from(*b))),
         (Value::Float(a), IntermediateValue::Numeric(b)) => Ok(a.partial_cmp(b).ok_or_else(|| Error::TypeError("numeric value".to_string()))?),
+        (Value::Int(a), IntermediateValue::Decimal(b)) => Ok(Decimal::from(*a).cmp(b)),
+        (Value::Float(a), IntermediateValue::Decimal(b)) => Ok(a.partial_cmp(&b.to_f64()).ok_or_else(|| Error::TypeError("numeric value".to_string()))?),
+        (Value::Decimal(a), IntermediateValue::Decimal(b)) => Ok(a.cmp(b)),
+        (Value::Decimal(a), IntermediateValue::Numeric(b)) => Ok(a.to_f64().partial_cmp(b).ok_or_else(|| Error::TypeError("numeric value".to_string()))?),
         (Value::Bool(a), IntermediateValue::Bool(b)) => Ok(a.cmp(b)),
         (Value::String(a), IntermediateValue::String(b)) => Ok(a.cmp(b)),
+        (Value::Date(a), IntermediateValue::Date(b)) => Ok(a.cmp(b)),
+        (Value::Date(a), IntermediateValue::Timestamp(b)) => Ok((a * SECONDS_PER_DAY).cmp(b)),
+        (Value::Timestamp(a), IntermediateValue::Timestamp(b)) => Ok(a.cmp(b)),
+        (Value::Timestamp(a), IntermediateValue::Date(b)) => Ok(a.cmp(&(b * SECONDS_PER_DAY))),
+        (Value::Bytes(a), IntermediateValue::Bytes(b)) => Ok(a.cmp(b)),
+        (Value::Uuid(a), IntermediateValue::Uuid(b)) => Ok(a.cmp(b)),
+        (Value::Uuid(a), IntermediateValue::String(b)) => Ok(a.cmp(&b.parse()?)),
+        (Value::List(a), IntermediateValue::List(b)) => {
+            for (x, y) in a.iter().zip(b.iter()) {
+                let ordering = compare_value_intermediate_value(x, y)?;
+                if ordering != Ordering::Equal {
+                    return Ok(ordering);
+                }
+            }
+            Ok(a.len().cmp(&b.len()))
+        },
+        (Value::Json(a), IntermediateValue::Json(b)) => Ok(a.compare(b)),
+        (Value::Enum(a, variants), IntermediateValue::String(b)) => variants
+            .iter()
+            .position(|variant| variant == b)
+            .map(|index| a.cmp(&index))
+            .ok_or_else(|| Error::TypeError(format!("'{}' is not one of {}", b, FieldType::Enum(variants.clone())))),
         (a, b) => Err(Error::TypeError(format!("Cannot compare field value {:?} with constant {:?}", a, b))),
     }
 }
@@ -0,0 +1,13 @@
+﻿use crate::commands::statement::SelectStatement;
+
+#[derive(Clone, Debug)]
+pub struct MaterializedView {
+    pub key: Vec<String>,
+    pub select: SelectStatement,
+}
+
+impl MaterializedView {
+    pub fn new(key: Vec<String>, select: SelectStatement) -> Self {
+        Self { key, select }
+    }
+}
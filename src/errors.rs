@@ -1,9 +1,39 @@
-﻿use thiserror::Error;
+﻿use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    pub width: usize,
+    pub source_line: String,
+}
+
+impl Diagnostic {
+    pub fn new(message: String, line: usize, column: usize, width: usize, source_line: String) -> Self {
+        Self { message, line, column, width: width.max(1), source_line }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let padding = " ".repeat(self.column.saturating_sub(1));
+        let available = self.source_line.chars().count().saturating_sub(self.column.saturating_sub(1)).max(1);
+        let caret = "^".repeat(self.width.min(available));
+
+        writeln!(f, "{} at line {}, column {}", self.message, self.line, self.column)?;
+        writeln!(f, "  {}", self.source_line)?;
+        write!(f, "  {}{}", padding, caret)
+    }
+}
 
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("ParseError: {0}")]
     ParseError(String),
+    #[error("SyntaxError: {0}")]
+    SyntaxError(Diagnostic),
     #[error("NoMatchError: {0}")]
     NoTokenError(String),
     #[error("AlreadyExistsError: {0}")]
@@ -18,12 +48,12 @@ pub enum Error {
     NotSpecifiedError(String),
     #[error("TypeError: {0}")]
     TypeError(String),
+    #[error("ConstraintViolationError: {0}")]
+    ConstraintViolationError(String),
     #[error("MissingFieldError: {0}")]
     MissingFieldError(String),
     #[error("InvalidComparisonError: {0}")]
     InvalidComparisonError(String),
     #[error("IOError: {0}")]
     IOError(String),
-    #[error("IOError: {0}")]
-    KeyTypeError(String),
 }
\ No newline at end of file
@@ -1,16 +1,13 @@
 use std::io::stdin;
 use clap::Parser;
 use rust_db::database::databases::{AnyDatabase};
-use rust_db::database::types::KeyType;
 use rust_db::errors::Error;
+use rust_db::parser::is_complete_command;
 
+/// A small SQL-like in-memory database that reads commands from stdin.
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
-struct Args {
-    /// Type of the database key (Int/String)
-    #[arg(short, long, default_value = "Int")]
-    key: String,
-}
+struct Args {}
 
 fn execute_command (database: &mut AnyDatabase, command_str: &str) {
     let result = database.execute_command(command_str);
@@ -21,14 +18,6 @@ fn execute_command (database: &mut AnyDatabase, command_str: &str) {
     };
 }
 
-fn get_database (key_type: String) -> Result<AnyDatabase, Error> {
-    match key_type.as_str() {
-        "string" => Ok(AnyDatabase::new(KeyType::String)),
-        "int" => Ok(AnyDatabase::new(KeyType::Int)),
-        _ => { Err(Error::KeyTypeError(format!("{} is not an invalid key type", key_type))) },
-    }
-}
-
 fn read_create_command(first: String) -> Result<String, Error> {
     let mut next = String::new();
     let bytes = read_line_trimmed(&mut next)?;
@@ -50,8 +39,8 @@ fn read_line_trimmed (buf: &mut String) -> Result<usize, Error> {
 }
 
 fn main() -> Result<(), Error> {
-    let args = Args::parse();
-    let mut db = get_database(args.key.to_lowercase())?;
+    Args::parse();
+    let mut db = AnyDatabase::new();
     let mut line = String::new();
 
     loop {
@@ -63,7 +52,7 @@ fn main() -> Result<(), Error> {
             continue;
         }
 
-        let command = if line.starts_with("CREATE") {
+        let command = if line.starts_with("CREATE") && !is_complete_command(&line) {
             read_create_command(line.clone())?
         } else {
             line.clone()
@@ -77,29 +66,19 @@ fn main() -> Result<(), Error> {
 
 #[cfg(test)]
 mod tests {
-    use rust_db::database::types::FieldType;
     use super::*;
 
-    #[test]
-    fn test_get_database() {
-        let db1 = get_database("int".to_string());
-        assert!(db1.is_ok());
-
-        let db2 = get_database("string".to_string());
-        assert!(db2.is_ok());
-
-        let db3 = get_database("float".to_string());
-        assert!(db3.is_err());
-    }
-
     #[test]
     fn test_execute_command() {
-        let mut db = AnyDatabase::new(KeyType::Int);
+        let mut db = AnyDatabase::new();
 
         db.execute_command("CREATE table KEY id
             FIELDS id: Int").unwrap();
+        db.execute_command("CREATE other KEY id
+            FIELDS id: String").unwrap();
 
-        assert_eq!(db.key_type(), FieldType::Int);
+        assert!(db.database().has_table(&"table".to_string()));
+        assert!(db.database().has_table(&"other".to_string()));
     }
 
     #[test]
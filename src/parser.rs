@@ -1,22 +1,25 @@
-﻿use pest::iterators::Pair;
+﻿use pest::error::{ErrorVariant, InputLocation, LineColLocation};
+use pest::iterators::Pair;
 use pest::Parser;
 use crate::commands::clauses::clause::AnyClause;
-use crate::commands::clauses::evaluable::{AnyEvaluable, CompOp, Comparison, ComparisonAnd, ComparisonOr};
+use crate::commands::clauses::evaluable::{AnyEvaluable, CompOp, Comparison, ComparisonAnd, ComparisonOr, FieldFunction};
 use crate::commands::clauses::limit::LimitClause;
 use crate::commands::clauses::order::OrderByClause;
 use crate::commands::clauses::r#where::WhereClause;
-use crate::errors::Error;
+use crate::errors::{Diagnostic, Error};
 use crate::commands::command::AnyCommand;
-use crate::commands::create::CreateCommand;
-use crate::commands::delete::DeleteCommand;
-use crate::commands::insert::InsertCommand;
-use crate::commands::read::ReadCommand;
-use crate::commands::save::SaveCommand;
-use crate::commands::select::SelectCommand;
+use crate::commands::statement::{CurrentTimeSpan, ParameterSpan, PreparedStatement, SelectStatement, Statement, StatementKey};
+use crate::database::constraint::{Constraint, OnDelete};
 use crate::database::databases::Database;
 use crate::database::key::DatabaseKey;
+use crate::database::decimal::Decimal;
+use crate::database::hex::parse_hex;
+use crate::database::record::Projection;
+use crate::database::json::Json;
+use crate::database::table::OnConflict;
+use crate::database::temporal::{add_seconds, now, parse_date, parse_timestamp, CurrentTime, DatePart, SECONDS_PER_DAY};
 use crate::database::types::FieldType;
-use crate::database::value::{IntermediateValue, KeyValue};
+use crate::database::value::{unescape_string, IntermediateValue, KeyValue};
 
 #[derive(pest_derive::Parser)]
 #[grammar = "commands.pest"]
@@ -30,7 +33,7 @@ fn expect_any_rule<'a>(pair: Option<Pair<'a, Rule>>, msg: &'static str) -> Resul
 fn expect_rule<'a>(pair: Option<Pair<'a, Rule>>, expected: Rule, msg: &'static str) -> Result<Pair<'a, Rule>, Error> {
     let pair = pair.ok_or_else(|| Error::NoTokenError(msg.to_string()))?;
     if pair.as_rule() != expected {
-        return Err(Error::UnknownTokenError(msg.to_string()));
+        return Err(unexpected_pair(&pair, expected));
     }
     Ok(pair)
 }
@@ -42,41 +45,259 @@ fn possible_rule<'a> (pair: Option<Pair<'a, Rule>>, expected: Rule, msg: &'stati
 
     let pair = pair.ok_or_else(|| Error::NoTokenError(msg.to_string()))?;
     if pair.as_rule() != expected {
-        return Err(Error::UnknownTokenError(msg.to_string()));
+        return Err(unexpected_pair(&pair, expected));
     }
     Ok(Some(pair))
 }
 
-pub fn parse_command<'a, K: DatabaseKey>(input: &'a str, database: &'a mut Database<K>) -> Result<AnyCommand<'a, K>, Error> {
-    let mut pairs = match QueryParser::parse(Rule::command, input.trim()) {
-        Ok(pairs) => pairs,
-        Err(e) => {
-            return Err(Error::ParseError(format!("Failed to parse command: {}", e)));
+fn describe_rule(rule: Rule) -> String {
+    let description = match rule {
+        Rule::EOI => "end of input",
+        Rule::command | Rule::single_command => "a command",
+        Rule::ident | Rule::ident_list | Rule::projection | Rule::projection_list | Rule::bare_ident | Rule::backtick_ident | Rule::bracket_ident => "a name",
+        Rule::decl | Rule::decl_list => "a field declaration such as 'year: Int'",
+        Rule::column_constraint => "a field constraint (UNIQUE, NULL, NOT NULL or REFERENCES)",
+        Rule::references => "a reference such as 'REFERENCES authors'",
+        Rule::on_delete_action => "a delete action (RESTRICT, CASCADE or SET NULL)",
+        Rule::check_constraint => "a constraint such as 'CHECK (year > 2000)'",
+        Rule::decl_type | Rule::decimal_type | Rule::bytes_type | Rule::uuid_type | Rule::list_type | Rule::json_type | Rule::enum_type | Rule::int_type | Rule::float_type | Rule::string_type | Rule::bool_type => "a type such as Int, Float, String, Bool, Date or Decimal(10, 2)",
+        Rule::assign | Rule::assign_list => "an assignment such as 'year = 2000'",
+        Rule::comp_op | Rule::equal | Rule::neq | Rule::leq | Rule::ltn | Rule::geq | Rule::gtn | Rule::null_test | Rule::IS => "a comparison operator (=, !=, <, <=, >, >=, CONTAINS or IS NULL)",
+        Rule::comparison | Rule::comparison_braced | Rule::comparison_and | Rule::comparison_or => "a condition such as 'year > 2000'",
+        Rule::any_type_def | Rule::arithmetic | Rule::numeric | Rule::bool | Rule::true_value | Rule::false_value | Rule::string => "a value",
+        Rule::key_type_def | Rule::key_tuple | Rule::int => "a key value",
+        Rule::key_fields => "a key such as 'id' or '(order_id, line_no)'",
+        Rule::bytes | Rule::hex => "a hex literal such as x\"deadbeef\"",
+        Rule::placeholder => "a parameter such as '?' or '$1'",
+        Rule::positive_int => "a positive number",
+        Rule::path => "a file path",
+        Rule::DROP_KW => "'DROP'",
+        Rule::temporal | Rule::date_literal | Rule::timestamp_literal => "a date such as DATE \"2024-01-31\"",
+        Rule::time_unit => "a time unit (DAYS, HOURS, MINUTES or SECONDS)",
+        Rule::date_part | Rule::field_call => "a function such as YEAR(field) or LENGTH(field)",
+        Rule::list | Rule::list_item => "a list such as [1, 2, 3]",
+        Rule::json | Rule::json_text => "a JSON literal such as JSON '{\"id\": 1}'",
+        Rule::json_path => "a JSON path such as payload->\"id\"",
+        _ => {
+            let name = format!("{:?}", rule);
+            return match name.chars().all(|c| c.is_ascii_uppercase() || c == '_') {
+                true => format!("'{}'", name),
+                false => name.replace('_', " "),
+            };
+        },
+    };
+
+    description.to_string()
+}
+
+fn describe_alternatives(rules: &[Rule]) -> String {
+    let mut rules = rules.to_vec();
+    rules.sort_by_key(|rule| *rule == Rule::EOI);
+
+    let mut descriptions: Vec<String> = Vec::new();
+    for rule in &rules {
+        let description = describe_rule(*rule);
+        if !descriptions.contains(&description) {
+            descriptions.push(description);
         }
+    }
+
+    match descriptions.split_last() {
+        None => "nothing".to_string(),
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} or {}", rest.join(", "), last),
+    }
+}
+
+fn found_token(input: &str) -> (String, usize) {
+    match input.split_whitespace().next() {
+        Some(token) => (format!("'{}'", token), token.chars().count()),
+        None => ("end of input".to_string(), 1),
+    }
+}
+
+fn syntax_error(error: pest::error::Error<Rule>, input: &str) -> Error {
+    let (line, column) = match error.line_col {
+        LineColLocation::Pos(position) | LineColLocation::Span(position, _) => position,
+    };
+    let offset = match error.location {
+        InputLocation::Pos(offset) | InputLocation::Span((offset, _)) => offset,
+    };
+    let (found, width) = found_token(input.get(offset..).unwrap_or_default());
+
+    let message = match &error.variant {
+        ErrorVariant::ParsingError { positives, .. } if !positives.is_empty() =>
+            format!("Expected {} but found {}", describe_alternatives(positives), found),
+        ErrorVariant::ParsingError { .. } => format!("Unexpected {}", found),
+        ErrorVariant::CustomError { message } => message.clone(),
     };
 
-    let parsed = pairs.as_str();
-    if parsed.len() != input.len() {
-        return Err(Error::ParseError(format!("Unexpected extra input after command: '{}'", &input[parsed.len()..])));
+    Error::SyntaxError(Diagnostic::new(message, line, column, width, error.line().to_string()))
+}
+
+fn pair_error(pair: &Pair<Rule>, message: String) -> Error {
+    let span = pair.as_span();
+    let (line, column) = span.start_pos().line_col();
+    let source_line = span.start_pos().line_of().trim_end_matches(['\r', '\n']).to_string();
+
+    Error::SyntaxError(Diagnostic::new(message, line, column, span.as_str().chars().count(), source_line))
+}
+
+fn unexpected_pair(pair: &Pair<Rule>, expected: Rule) -> Error {
+    pair_error(pair, format!("Expected {} but found '{}'", describe_rule(expected), pair.as_str()))
+}
+
+pub fn parse_command<'a, K: DatabaseKey>(input: &'a str, database: &'a mut Database<K>) -> Result<AnyCommand<'a, K>, Error> {
+    prepare_command(input)?.bind(&[], now())?.into_command(database)
+}
+
+pub fn prepare_command(input: &str) -> Result<PreparedStatement, Error> {
+    let statement = prepare_statement(input)?;
+
+    if !statement.parameters().is_empty() {
+        return Err(Error::ParseError("Commands with parameters have to be prepared".to_string()));
     }
 
-    let command_pair = expect_rule(pairs.next(), Rule::command, "Expected a command")?;
+    Ok(statement)
+}
+
+pub fn prepare_statement(input: &str) -> Result<PreparedStatement, Error> {
+    let (statement, parameters, current_times) = parse_statement(input)?;
+
+    Ok(PreparedStatement::new(input.trim().to_string(), statement, parameters, current_times))
+}
+
+fn parse_statement(input: &str) -> Result<(Statement, Vec<ParameterSpan>, Vec<CurrentTimeSpan>), Error> {
+    let input = input.trim();
+    let mut pairs = QueryParser::parse(Rule::single_command, input)
+        .map_err(|e| syntax_error(e, input))?;
+
+    let single_command_pair = expect_rule(pairs.next(), Rule::single_command, "Expected a command")?;
+    let command_pair = expect_rule(single_command_pair.into_inner().next(), Rule::command, "Expected a command")?;
+    let parameters = number_placeholders(command_pair.clone())?;
+    let current_times = find_current_times(command_pair.clone())?;
     let query = expect_any_rule(command_pair.into_inner().next(), "Empty command")?;
 
-    match query.as_rule() {
-        Rule::create_query => { parse_create_query(query, database) },
-        Rule::select_query => { parse_select_query(query, database) },
-        Rule::insert_query => { parse_insert_query(query, database) },
-        Rule::delete_query => { parse_delete_query(query, database) },
-        Rule::read_query => { parse_read_query(query, database) },
-        Rule::save_query => { parse_save_query(query, database) },
-        _ => Err(Error::UnknownTokenError(String::from("Unexpected token in command")))
+    let mut statement = match query.as_rule() {
+        Rule::create_query => { parse_create_query(query)? },
+        Rule::create_select_query => { parse_create_select_query(query)? },
+        Rule::create_view_query => { parse_create_view_query(query)? },
+        Rule::create_materialized_view_query => { parse_create_materialized_view_query(query)? },
+        Rule::drop_view_query => { parse_drop_view_query(query)? },
+        Rule::create_index_query => { parse_create_index_query(query)? },
+        Rule::refresh_query => { parse_refresh_query(query)? },
+        Rule::select_query => { parse_select_query(query)? },
+        Rule::insert_query => { parse_insert_query(query)? },
+        Rule::insert_select_query => { parse_insert_select_query(query)? },
+        Rule::delete_query => { parse_delete_query(query)? },
+        Rule::explain_query => { parse_explain_query(query)? },
+        Rule::show_tables_query => { Statement::ShowTables },
+        Rule::describe_query => { parse_describe_query(query)? },
+        Rule::read_query => { parse_read_query(query)? },
+        Rule::save_query => { parse_save_query(query)? },
+        _ => { return Err(Error::UnknownTokenError(String::from("Unexpected token in command"))); }
+    };
+
+    let mut indices = parameters.iter().map(|parameter| parameter.index);
+    for value in statement.values_mut() {
+        if let IntermediateValue::Parameter(n) = value {
+            *n = indices.next()
+                .ok_or_else(|| Error::ParseError("Mismatched parameter placeholders".to_string()))?;
+        }
+    }
+
+    Ok((statement, parameters, current_times))
+}
+
+fn number_placeholders(command_pair: Pair<Rule>) -> Result<Vec<ParameterSpan>, Error> {
+    let mut parameters = Vec::new();
+    let mut positional = false;
+    let mut numbered = false;
+
+    for pair in command_pair.into_inner().flatten().filter(|pair| pair.as_rule() == Rule::placeholder) {
+        let span = pair.as_span();
+        let index = match pair.as_str().strip_prefix('$') {
+            Some(number) => {
+                numbered = true;
+                number.parse::<usize>()
+                    .map_err(|e| Error::ParseError(format!("Failed to parse parameter number: {}", e)))?
+            },
+            None => {
+                positional = true;
+                parameters.len() + 1
+            },
+        };
+
+        parameters.push(ParameterSpan { start: span.start(), end: span.end(), index });
+    }
+
+    if positional && numbered {
+        return Err(Error::ParseError("Cannot mix '?' and '$n' parameters".to_string()));
+    }
+
+    Ok(parameters)
+}
+
+fn find_current_times(command_pair: Pair<Rule>) -> Result<Vec<CurrentTimeSpan>, Error> {
+    command_pair
+        .into_inner()
+        .flatten()
+        .filter(|pair| pair.as_rule() == Rule::temporal)
+        .filter_map(|pair| {
+            let span = pair.as_span();
+            match parse_temporal(pair) {
+                Ok(IntermediateValue::Current(current)) => Some(Ok(CurrentTimeSpan { start: span.start(), end: span.end(), current })),
+                Ok(_) => None,
+                Err(e) => Some(Err(e)),
+            }
+        })
+        .collect()
+}
+
+pub fn is_complete_command(input: &str) -> bool {
+    match QueryParser::parse(Rule::command, input.trim()) {
+        Ok(pairs) => pairs.as_str().len() == input.trim().len(),
+        Err(_) => false,
     }
 }
 
 pub fn parse_ident(ident_pair: Pair<Rule>) -> Result<String, Error> {
     let pair = expect_rule(Some(ident_pair), Rule::ident, "Expected an identifier")?;
-    Ok(pair.as_str().to_string())
+    let name = expect_any_rule(pair.into_inner().next(), "Expected an identifier")?;
+
+    match name.as_rule() {
+        Rule::backtick_ident => Ok(name.as_str().replace("``", "`")),
+        _ => Ok(name.as_str().to_string()),
+    }
+}
+
+pub fn parse_json_path(json_path_pair: Pair<Rule>) -> Result<(String, Vec<String>), Error> {
+    let mut json_path = json_path_pair.into_inner();
+
+    let field = parse_ident(expect_rule(json_path.next(), Rule::ident, "Missing or invalid identifier")?)?;
+    let keys = json_path.map(|key_pair| unescape_string(key_pair.as_str())).collect();
+
+    Ok((field, keys))
+}
+
+pub fn parse_projection_list(projection_list_pair: Pair<Rule>) -> Result<Vec<Projection>, Error> {
+    let mut projections = Vec::new();
+
+    for projection_pair in projection_list_pair.into_inner() {
+        let inner_pair = expect_any_rule(projection_pair.into_inner().next(), "Expected a field")?;
+
+        let projection = match inner_pair.as_rule() {
+            Rule::json_path => {
+                let (field, keys) = parse_json_path(inner_pair)?;
+                Projection::new(field, keys)
+            },
+            _ => Projection::from(parse_ident(inner_pair)?),
+        };
+
+        projections.push(projection);
+    }
+
+    Ok(projections)
 }
 
 pub fn parse_ident_list(ident_list_pair: Pair<Rule>) -> Result<Vec<String>, Error> {
@@ -92,21 +313,114 @@ pub fn parse_ident_list(ident_list_pair: Pair<Rule>) -> Result<Vec<String>, Erro
 
 pub fn parse_numeric(numeric_pair: Pair<Rule>) -> Result<IntermediateValue, Error> {
     let pair = expect_rule(Some(numeric_pair), Rule::numeric, "Expected an numeric")?;
-    let float = pair.as_str().parse::<f64>()
-        .map_err(|e| Error::ParseError(format!("Failed to parse numeric: {}", e)))?;
-    Ok(IntermediateValue::Numeric(float))
+
+    if pair.as_str().contains('.') {
+        return match pair.as_str().parse::<Decimal>() {
+            Ok(decimal) => Ok(IntermediateValue::Decimal(decimal)),
+            Err(_) => pair.as_str().parse::<f64>()
+                .map(IntermediateValue::Numeric)
+                .map_err(|_| pair_error(&pair, format!("Invalid number {}", pair.as_str()))),
+        };
+    }
+
+    let integer = pair.as_str().parse::<i64>()
+        .map_err(|_| pair_error(&pair, format!("Integer {} is out of range ({} to {})", pair.as_str(), i64::MIN, i64::MAX)))?;
+    Ok(IntermediateValue::Int(integer))
+}
+
+pub fn parse_arithmetic(arithmetic_pair: Pair<Rule>) -> Result<IntermediateValue, Error> {
+    let source = arithmetic_pair.as_str().to_string();
+    let overflow = || Error::TypeError(format!("{} overflows", source));
+    let mut pairs = arithmetic_pair.into_inner();
+    let mut integral = true;
+
+    let mut operand = |pair: Pair<Rule>| -> Result<Decimal, Error> {
+        match parse_numeric(pair.clone())? {
+            IntermediateValue::Int(i) => Ok(Decimal::from(i)),
+            IntermediateValue::Decimal(d) => {
+                integral = false;
+                Ok(d)
+            },
+            _ => Err(pair_error(&pair, format!("Decimal {} has more than {} digits", pair.as_str(), Decimal::MAX_PRECISION))),
+        }
+    };
+
+    // Products bind tighter than sums, so `terms` holds the summands with their sign.
+    let mut terms = vec![(false, operand(expect_rule(pairs.next(), Rule::numeric, "Expected a number")?)?)];
+
+    while let Some(op_pair) = pairs.next() {
+        let value = operand(expect_rule(pairs.next(), Rule::numeric, "Expected a number")?)?;
+
+        match op_pair.as_rule() {
+            Rule::times => {
+                let (negative, last) = terms.pop().ok_or_else(overflow)?;
+                terms.push((negative, last.checked_mul(&value).ok_or_else(overflow)?));
+            },
+            Rule::minus => terms.push((true, value)),
+            _ => terms.push((false, value)),
+        }
+    }
+
+    let result = terms.into_iter().try_fold(Decimal::from(0), |sum, (negative, term)| match negative {
+        true => sum.checked_sub(&term),
+        false => sum.checked_add(&term),
+    }).ok_or_else(overflow)?;
+
+    match integral {
+        true => result.to_i64().map(IntermediateValue::Int).ok_or_else(overflow),
+        false => Ok(IntermediateValue::Decimal(result)),
+    }
 }
 
 pub fn parse_string(string_pair: Pair<Rule>) -> Result<IntermediateValue, Error> {
     let pair = expect_rule(Some(string_pair), Rule::string, "Expected a string")?;
-    let string = pair.as_str().to_string();
+    let string = unescape_string(pair.as_str());
     Ok(IntermediateValue::String(string))
 }
 
+pub fn parse_list(list_pair: Pair<Rule>) -> Result<IntermediateValue, Error> {
+    let items = list_pair.into_inner()
+        .map(parse_any_type_def)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(IntermediateValue::List(items))
+}
+
+pub fn parse_bytes(bytes_pair: Pair<Rule>) -> Result<IntermediateValue, Error> {
+    let pair = expect_rule(Some(bytes_pair), Rule::bytes, "Expected a hex literal")?;
+    let hex_pair = expect_rule(pair.into_inner().next(), Rule::hex, "Expected a hex literal")?;
+
+    let bytes = parse_hex(hex_pair.as_str())
+        .map_err(|error| match error {
+            Error::ParseError(message) => pair_error(&hex_pair, message),
+            other => other,
+        })?;
+
+    Ok(IntermediateValue::Bytes(bytes))
+}
+
+pub fn parse_json(json_pair: Pair<Rule>) -> Result<IntermediateValue, Error> {
+    let pair = expect_rule(Some(json_pair), Rule::json, "Expected a JSON literal")?;
+    let text_pair = expect_rule(pair.into_inner().nth(1), Rule::json_text, "Expected a JSON literal")?;
+
+    let json = text_pair.as_str().parse::<Json>()
+        .map_err(|e| match e {
+            Error::TypeError(message) => pair_error(&text_pair, message),
+            other => other,
+        })?;
+
+    Ok(IntermediateValue::Json(json))
+}
+
+pub fn parse_placeholder(placeholder_pair: Pair<Rule>) -> Result<IntermediateValue, Error> {
+    expect_rule(Some(placeholder_pair), Rule::placeholder, "Expected a parameter")?;
+    Ok(IntermediateValue::Parameter(0))
+}
+
 pub fn parse_key_int(int_pair: Pair<Rule>) -> Result<KeyValue, Error> {
     let pair = expect_rule(Some(int_pair), Rule::int, "Expected an integer")?;
     let integer = pair.as_str().parse::<i64>()
-        .map_err(|e| Error::ParseError(format!("Failed to parse int: {}", e)))?;
+        .map_err(|_| pair_error(&pair, format!("Integer {} is out of range ({} to {})", pair.as_str(), i64::MIN, i64::MAX)))?;
     Ok(KeyValue::Int(integer))
 }
 
@@ -119,7 +433,7 @@ pub fn parse_positive_int(positive_int_pair: Pair<Rule>) -> Result<usize, Error>
 
 pub fn parse_key_string(string_pair: Pair<Rule>) -> Result<KeyValue, Error> {
     let pair = expect_rule(Some(string_pair), Rule::string, "Expected a string")?;
-    let string = pair.as_str().to_string();
+    let string = unescape_string(pair.as_str());
     Ok(KeyValue::String(string))
 }
 
@@ -143,6 +457,7 @@ pub fn parse_comp_op(comp_op_pair: Pair<Rule>) -> Result<CompOp, Error> {
         Rule::ltn => Ok(CompOp::Lt),
         Rule::geq => Ok(CompOp::Geq),
         Rule::gtn => Ok(CompOp::Gt),
+        Rule::CONTAINS => Ok(CompOp::Contains),
         _ => Err(Error::UnknownTokenError(String::from("Unexpected token in comp_op")))
     }
 }
@@ -155,63 +470,208 @@ pub fn parse_decl_type(decl_type_pair: Pair<Rule>) -> Result<FieldType, Error> {
         Rule::float_type => Ok(FieldType::Float),
         Rule::string_type => Ok(FieldType::String),
         Rule::bool_type => Ok(FieldType::Bool),
+        Rule::date_type => Ok(FieldType::Date),
+        Rule::timestamp_type => Ok(FieldType::Timestamp),
+        Rule::bytes_type => Ok(FieldType::Bytes),
+        Rule::uuid_type => Ok(FieldType::Uuid),
+        Rule::json_type => Ok(FieldType::Json),
+        Rule::enum_type => {
+            let mut variants: Vec<String> = Vec::new();
+
+            for variant_pair in type_pair.into_inner() {
+                let variant = unescape_string(variant_pair.as_str());
+                if variants.contains(&variant) {
+                    return Err(pair_error(&variant_pair, format!("Enum value \"{}\" is declared more than once", variant)));
+                }
+                variants.push(variant);
+            }
+
+            Ok(FieldType::Enum(variants.into()))
+        },
+        Rule::list_type => {
+            let item_pair = expect_rule(type_pair.into_inner().next(), Rule::decl_type, "Expected a list element type")?;
+            Ok(FieldType::List(Box::new(parse_decl_type(item_pair)?)))
+        },
+        Rule::decimal_type => parse_decimal_type(type_pair),
         _ => Err(Error::UnknownTokenError(String::from("Unknown or invalid type")))
     }
 }
 
+pub fn parse_decimal_type(decimal_type_pair: Pair<Rule>) -> Result<FieldType, Error> {
+    let mut items = decimal_type_pair.clone().into_inner();
+
+    let precision_pair = expect_rule(items.next(), Rule::positive_int, "Expected a precision")?;
+    let scale_pair = expect_rule(items.next(), Rule::scale, "Expected a scale")?;
+
+    let precision = parse_positive_int(precision_pair)? as u32;
+    let scale = scale_pair.as_str().parse::<u32>()
+        .map_err(|e| Error::ParseError(format!("Failed to parse scale: {}", e)))?;
+
+    if precision > Decimal::MAX_PRECISION || scale > precision {
+        return Err(pair_error(&decimal_type_pair, format!("Decimal precision must be at most {} and scale at most the precision", Decimal::MAX_PRECISION)));
+    }
+
+    Ok(FieldType::Decimal(precision, scale))
+}
+
 pub fn parse_any_type_def(decl_type_pair: Pair<Rule>) -> Result<IntermediateValue, Error> {
     let type_pair = expect_any_rule(decl_type_pair.into_inner().next(), "Expected type declaration")?;
 
     match type_pair.as_rule() {
+        Rule::list => parse_list(type_pair),
+        Rule::json => parse_json(type_pair),
+        Rule::temporal => parse_temporal(type_pair),
+        Rule::bytes => parse_bytes(type_pair),
+        Rule::arithmetic => parse_arithmetic(type_pair),
         Rule::numeric => parse_numeric(type_pair),
         Rule::string => parse_string(type_pair),
         Rule::bool => parse_bool(type_pair),
+        Rule::NULL => Ok(IntermediateValue::Null),
+        Rule::placeholder => parse_placeholder(type_pair),
         _ => Err(Error::UnknownTokenError(String::from("Unknown or invalid type")))
     }
 }
 
-pub fn parse_key_type_def(key_type_pair: Pair<Rule>) -> Result<KeyValue, Error> {
+pub fn parse_key_type_def(key_type_pair: Pair<Rule>) -> Result<StatementKey, Error> {
     let type_pair = expect_any_rule(key_type_pair.into_inner().next(), "Expected type declaration")?;
 
     match type_pair.as_rule() {
-        Rule::string => parse_key_string(type_pair),
-        Rule::int => parse_key_int(type_pair),
+        Rule::string => Ok(StatementKey::Literal(parse_key_string(type_pair)?)),
+        Rule::int => Ok(StatementKey::Literal(parse_key_int(type_pair)?)),
+        Rule::placeholder => Ok(StatementKey::Bound(parse_placeholder(type_pair)?)),
         _ => Err(Error::UnknownTokenError(String::from("Unknown or invalid type")))
     }
 }
 
+pub fn parse_key_fields(key_fields_pair: Pair<Rule>) -> Result<Vec<String>, Error> {
+    let fields_pair = expect_any_rule(key_fields_pair.into_inner().next(), "Expected a key")?;
+
+    match fields_pair.as_rule() {
+        Rule::ident_list => parse_ident_list(fields_pair),
+        _ => Ok(vec![parse_ident(fields_pair)?]),
+    }
+}
+
 pub fn parse_path(path_pair: Pair<Rule>) -> Result<String, Error> {
     let pair = expect_rule(Some(path_pair), Rule::path, "Expected a path")?;
     let path = pair.as_str().to_string();
     Ok(path)
 }
 
-pub fn parse_create_query<'a, K: DatabaseKey>(create_query_pair: Pair<Rule>, database: &'a mut Database<K>) -> Result<AnyCommand<'a, K>, Error> {
-    let items: Vec<_> = create_query_pair.into_inner().collect();
+pub fn parse_create_query(create_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let mut inner_rules = create_query_pair.into_inner().peekable();
+
+    let name_pair = expect_rule(inner_rules.nth(1), Rule::ident, "Missing or invalid name ident")?;
+    let key_pair = expect_rule(inner_rules.nth(1), Rule::key_fields, "Missing or invalid key ident")?;
+    let auto = inner_rules.next_if(|pair| pair.as_rule() == Rule::AUTO).is_some();
+    let fields_pair = expect_rule(inner_rules.nth(1), Rule::decl_list, "Missing or invalid fields list")?;
+
+    let name = parse_ident(name_pair)?;
+    let key = parse_key_fields(key_pair)?;
+    let (fields, mut constraints, nullable) = parse_decl_list(fields_pair)?;
+
+    for check_pair in inner_rules {
+        constraints.push(parse_check_constraint(check_pair)?);
+    }
+
+    Ok(Statement::Create { name, key, auto, fields, constraints, nullable })
+}
+
+pub fn parse_create_select_query(create_select_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let items: Vec<_> = create_select_query_pair.into_inner().collect();
 
     let name_pair = expect_rule(items.get(1).cloned(), Rule::ident, "Missing or invalid name ident")?;
-    let key_pair = expect_rule(items.get(3).cloned(), Rule::ident, "Missing or invalid key ident")?;
-    let fields_pair = expect_rule(items.get(5).cloned(), Rule::decl_list, "Missing or invalid fields list")?;
+    let key_pair = expect_rule(items.get(3).cloned(), Rule::key_fields, "Missing or invalid key ident")?;
+    let select_pair = expect_rule(items.get(5).cloned(), Rule::select_query, "Missing or invalid select query")?;
 
     let name = parse_ident(name_pair)?;
-    let key = parse_ident(key_pair)?;
-    let fields = parse_decl_list(fields_pair)?;
+    let key = parse_key_fields(key_pair)?;
+    let select = parse_select_statement(select_pair)?;
+
+    Ok(Statement::CreateSelect { name, key, select })
+}
+
+pub fn parse_create_view_query(create_view_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let items: Vec<_> = create_view_query_pair.into_inner().collect();
+
+    let name_pair = expect_rule(items.get(2).cloned(), Rule::ident, "Missing or invalid view name ident")?;
+    let select_pair = expect_rule(items.get(4).cloned(), Rule::select_query, "Missing or invalid select query")?;
+
+    let name = parse_ident(name_pair)?;
+    let select = parse_select_statement(select_pair)?;
+
+    Ok(Statement::CreateView { name, select })
+}
 
-    Ok(AnyCommand::Create(CreateCommand::new(database, name, key, fields)))
+pub fn parse_create_materialized_view_query(create_materialized_view_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let mut items = create_materialized_view_query_pair.into_inner().skip(3).peekable();
+
+    let name_pair = expect_rule(items.next(), Rule::ident, "Missing or invalid view name ident")?;
+    let name = parse_ident(name_pair)?;
+
+    let key = match items.next_if(|pair| pair.as_rule() == Rule::KEY) {
+        Some(_) => Some(parse_key_fields(expect_rule(items.next(), Rule::key_fields, "Missing or invalid key ident")?)?),
+        None => None,
+    };
+
+    expect_rule(items.next(), Rule::AS, "Missing AS")?;
+    let select_pair = expect_rule(items.next(), Rule::select_query, "Missing or invalid select query")?;
+    let select = parse_select_statement(select_pair)?;
+
+    Ok(Statement::CreateMaterializedView { name, key, select })
+}
+
+pub fn parse_refresh_query(refresh_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let name_pair = expect_rule(refresh_query_pair.into_inner().nth(1), Rule::ident, "Missing or invalid view name ident")?;
+
+    let name = parse_ident(name_pair)?;
+
+    Ok(Statement::Refresh { name })
+}
+
+pub fn parse_create_index_query(create_index_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let mut inner_rules = create_index_query_pair.into_inner();
+
+    let table_pair = expect_rule(inner_rules.nth(3), Rule::ident, "Missing or invalid table name ident")?;
+    let field_pair = expect_rule(inner_rules.next(), Rule::ident, "Missing or invalid field name ident")?;
+
+    let table = parse_ident(table_pair)?;
+    let field = parse_ident(field_pair)?;
+
+    Ok(Statement::CreateIndex { table, field })
+}
+
+pub fn parse_drop_view_query(drop_view_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let name_pair = expect_rule(drop_view_query_pair.into_inner().nth(2), Rule::ident, "Missing or invalid view name ident")?;
+
+    let name = parse_ident(name_pair)?;
+
+    Ok(Statement::DropView { name })
 }
 
-pub fn parse_decl_list(decl_list_pair: Pair<Rule>) -> Result<Vec<(String, FieldType)>, Error> {
+pub type DeclList = (Vec<(String, FieldType)>, Vec<Constraint>, Vec<String>);
+pub type Decl = (String, FieldType, Vec<Constraint>, bool);
+
+pub fn parse_decl_list(decl_list_pair: Pair<Rule>) -> Result<DeclList, Error> {
     let mut fields = Vec::new();
+    let mut constraints = Vec::new();
+    let mut nullable = Vec::new();
 
     for decl_pair in decl_list_pair.into_inner() {
-        let (key, field_type) = parse_decl(decl_pair)?;
+        let (key, field_type, field_constraints, is_nullable) = parse_decl(decl_pair)?;
+
+        if is_nullable {
+            nullable.push(key.clone());
+        }
+
+        constraints.extend(field_constraints);
         fields.push((key, field_type));
     }
 
-    Ok(fields)
+    Ok((fields, constraints, nullable))
 }
 
-pub fn parse_decl(decl_pair: Pair<Rule>) -> Result<(String, FieldType), Error> {
+pub fn parse_decl(decl_pair: Pair<Rule>) -> Result<Decl, Error> {
     let mut decl = decl_pair.into_inner();
 
     let key_pair = expect_rule(decl.next(), Rule::ident, "Missing field name")?;
@@ -220,10 +680,74 @@ pub fn parse_decl(decl_pair: Pair<Rule>) -> Result<(String, FieldType), Error> {
     let key = parse_ident(key_pair)?;
     let field_type = parse_decl_type(type_pair)?;
 
-    Ok((key, field_type))
+    let mut constraints = Vec::new();
+    let mut nullable = None;
+
+    for constraint_pair in decl {
+        let inner_pair = constraint_pair.into_inner().next()
+            .ok_or_else(|| Error::NoTokenError("Missing field constraint".to_string()))?;
+
+        match inner_pair.as_rule() {
+            Rule::UNIQUE => constraints.push(Constraint::Unique { field: key.clone() }),
+            Rule::NULL | Rule::not_null => {
+                let is_nullable = inner_pair.as_rule() == Rule::NULL;
+                if nullable.is_some_and(|nullable| nullable != is_nullable) {
+                    return Err(pair_error(&inner_pair, format!("Field '{}' cannot be both NULL and NOT NULL", key)));
+                }
+                nullable = Some(is_nullable);
+            },
+            Rule::references => constraints.push(parse_references(inner_pair, &key)?),
+            _ => return Err(Error::UnknownTokenError(String::from("Unknown field constraint"))),
+        }
+    }
+
+    Ok((key, field_type, constraints, nullable.unwrap_or(false)))
+}
+
+pub fn parse_references(references_pair: Pair<Rule>, field: &str) -> Result<Constraint, Error> {
+    let mut inner_rules = references_pair.into_inner();
+
+    let table_pair = expect_rule(inner_rules.nth(1), Rule::ident, "Missing referenced table")?;
+    let table = parse_ident(table_pair)?;
+
+    let on_delete = match inner_rules.nth(2) {
+        None => OnDelete::Restrict,
+        Some(action_pair) => {
+            let action_pair = expect_rule(Some(action_pair), Rule::on_delete_action, "Missing delete action")?;
+
+            match action_pair.clone().into_inner().next().map(|pair| pair.as_rule()) {
+                Some(Rule::RESTRICT) => OnDelete::Restrict,
+                Some(Rule::CASCADE) => OnDelete::Cascade,
+                Some(Rule::SET) => OnDelete::SetNull,
+                _ => return Err(Error::UnknownTokenError(String::from("Unknown delete action"))),
+            }
+        },
+    };
+
+    Ok(Constraint::References { field: field.to_string(), table, on_delete })
+}
+
+pub fn parse_check_constraint(check_constraint_pair: Pair<Rule>) -> Result<Constraint, Error> {
+    let mut inner_rules = check_constraint_pair.into_inner().peekable();
+
+    let name = match inner_rules.next_if(|pair| pair.as_rule() == Rule::CONSTRAINT) {
+        Some(_) => Some(parse_ident(expect_rule(inner_rules.next(), Rule::ident, "Missing constraint name")?)?),
+        None => None,
+    };
+
+    let condition_pair = expect_rule(inner_rules.nth(1), Rule::comparison_or, "Missing or invalid condition")?;
+    let condition = parse_comparison_or(condition_pair)?;
+
+    Ok(Constraint::Check { name, condition })
+}
+
+pub fn parse_select_query(select_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let select = parse_select_statement(select_query_pair)?;
+
+    Ok(Statement::Select(select))
 }
 
-pub fn parse_select_query<'a, K: DatabaseKey>(select_query_pair: Pair<'a, Rule>, database: &'a mut Database<K>) -> Result<AnyCommand<'a, K>, Error> {
+pub fn parse_select_statement(select_query_pair: Pair<Rule>) -> Result<SelectStatement, Error> {
     let mut inner_rules = select_query_pair.into_inner();
 
     let select_clause_pair = expect_rule(inner_rules.next(), Rule::select_clause, "Missing Select clause")?;
@@ -231,7 +755,6 @@ pub fn parse_select_query<'a, K: DatabaseKey>(select_query_pair: Pair<'a, Rule>,
 
     let fields = parse_select_clause(select_clause_pair)?;
     let table_id = parse_from_clause(from_clause_pair)?;
-    let table = database.get_table(&table_id)?;
 
     let mut clauses = Vec::new();
 
@@ -250,15 +773,15 @@ pub fn parse_select_query<'a, K: DatabaseKey>(select_query_pair: Pair<'a, Rule>,
         }
     }
 
-    Ok(AnyCommand::Select(SelectCommand::new(table, fields, clauses)))
+    Ok(SelectStatement { table: table_id, fields, clauses })
 }
 
-pub fn parse_select_clause(select_clause_pair: Pair<Rule>) -> Result<Vec<String>, Error> {
+pub fn parse_select_clause(select_clause_pair: Pair<Rule>) -> Result<Vec<Projection>, Error> {
     let mut select_clause = select_clause_pair.into_inner();
     
-    let fields_pair = expect_rule(select_clause.nth(1), Rule::ident_list, "Missing or invalid fields list")?;
+    let fields_pair = expect_rule(select_clause.nth(1), Rule::projection_list, "Missing or invalid fields list")?;
 
-    let fields = parse_ident_list(fields_pair)?;
+    let fields = parse_projection_list(fields_pair)?;
 
     Ok(fields)
 }
@@ -328,15 +851,109 @@ pub fn parse_comparison_braced(comparison_braced_pair: Pair<Rule>) -> Result<Any
 pub fn parse_comparison(comparison_pair: Pair<Rule>) -> Result<AnyEvaluable, Error> {
     let mut comparison = comparison_pair.into_inner();
 
-    let field_pair = expect_rule(comparison.next(), Rule::ident, "Missing or invalid identifier")?;
-    let op_pair = expect_rule(comparison.next(), Rule::comp_op, "Missing or invalid operator")?;
+    let operand_pair = expect_any_rule(comparison.next(), "Missing or invalid identifier")?;
+    let op_pair = expect_any_rule(comparison.next(), "Missing or invalid operator")?;
+
+    if op_pair.as_rule() == Rule::null_test {
+        let op = match op_pair.into_inner().nth(1).map(|pair| pair.as_rule()) {
+            Some(Rule::NOT) => CompOp::IsNot,
+            _ => CompOp::Is,
+        };
+        return Ok(AnyEvaluable::Comp(Comparison::new(parse_ident(operand_pair)?, op, IntermediateValue::Null)));
+    }
+
     let constant_pair = expect_rule(comparison.next(), Rule::any_type_def, "Missing or invalid constant")?;
 
-    let field = parse_ident(field_pair)?;
     let op = parse_comp_op(op_pair)?;
-    let constant = parse_any_type_def(constant_pair)?;
+    let constant = parse_any_type_def(constant_pair.clone())?;
+
+    if let IntermediateValue::Null = constant {
+        return Err(pair_error(&constant_pair, format!("Cannot compare with NULL using {}; use IS NULL or IS NOT NULL", op)));
+    }
+
+    let comparison = match operand_pair.as_rule() {
+        Rule::field_call => {
+            let mut call = operand_pair.into_inner();
+            let function_pair = expect_any_rule(call.next(), "Missing or invalid function")?;
+            let function = match function_pair.as_rule() {
+                Rule::LENGTH => FieldFunction::Length,
+                _ => FieldFunction::Extract(parse_date_part(function_pair)?),
+            };
+            let field = parse_ident(expect_rule(call.next(), Rule::ident, "Missing or invalid identifier")?)?;
+            Comparison::with_function(field, function, op, constant)
+        },
+        Rule::json_path => {
+            let (field, keys) = parse_json_path(operand_pair)?;
+            Comparison::with_function(field, FieldFunction::Path(keys), op, constant)
+        },
+        _ => Comparison::new(parse_ident(operand_pair)?, op, constant),
+    };
+
+    Ok(AnyEvaluable::Comp(comparison))
+}
+
+pub fn parse_date_part(date_part_pair: Pair<Rule>) -> Result<DatePart, Error> {
+    let part_pair = expect_any_rule(date_part_pair.into_inner().next(), "Expected a date part")?;
+
+    match part_pair.as_rule() {
+        Rule::YEAR => Ok(DatePart::Year),
+        Rule::MONTH => Ok(DatePart::Month),
+        Rule::DAY => Ok(DatePart::Day),
+        Rule::HOUR => Ok(DatePart::Hour),
+        Rule::MINUTE => Ok(DatePart::Minute),
+        Rule::SECOND => Ok(DatePart::Second),
+        _ => Err(Error::UnknownTokenError(String::from("Unknown or invalid date part")))
+    }
+}
+
+pub fn parse_temporal(temporal_pair: Pair<Rule>) -> Result<IntermediateValue, Error> {
+    let mut items = temporal_pair.into_inner();
+    let value_pair = expect_any_rule(items.next(), "Expected a date or timestamp")?;
+
+    let mut value = match value_pair.as_rule() {
+        Rule::date_literal | Rule::timestamp_literal => {
+            let is_date = value_pair.as_rule() == Rule::date_literal;
+            let string_pair = expect_rule(value_pair.into_inner().nth(1), Rule::string, "Expected a quoted date")?;
+            let parsed = match is_date {
+                true => parse_date(string_pair.as_str()).map(IntermediateValue::Date),
+                false => parse_timestamp(string_pair.as_str()).map(IntermediateValue::Timestamp),
+            };
+            parsed.map_err(|e| match e {
+                Error::ParseError(message) => pair_error(&string_pair, message),
+                other => other,
+            })?
+        },
+        Rule::now_call => IntermediateValue::Current(CurrentTime { date: false, intervals: Vec::new() }),
+        Rule::today_call => IntermediateValue::Current(CurrentTime { date: true, intervals: Vec::new() }),
+        _ => return Err(Error::UnknownTokenError(String::from("Unknown or invalid date"))),
+    };
 
-    Ok(AnyEvaluable::Comp(Comparison::new(field, op, constant)))
+    for interval_pair in items {
+        let overflow = || pair_error(&interval_pair, "Date arithmetic overflows".to_string());
+        let mut interval = interval_pair.clone().into_inner();
+        let sign_pair = expect_any_rule(interval.next(), "Expected + or -")?;
+        let amount = parse_positive_int(expect_rule(interval.next(), Rule::positive_int, "Expected an amount")?)? as i64;
+        let unit_pair = expect_rule(interval.next(), Rule::time_unit, "Expected a time unit")?;
+        let unit = expect_any_rule(unit_pair.into_inner().next(), "Expected a time unit")?;
+
+        let amount = if sign_pair.as_rule() == Rule::minus { -amount } else { amount };
+        let seconds = match unit.as_rule() {
+            Rule::DAY => amount.checked_mul(SECONDS_PER_DAY),
+            Rule::HOUR => amount.checked_mul(3600),
+            Rule::MINUTE => amount.checked_mul(60),
+            _ => Some(amount),
+        }.ok_or_else(overflow)?;
+
+        value = match value {
+            IntermediateValue::Current(mut current) => {
+                current.intervals.push(seconds);
+                IntermediateValue::Current(current)
+            },
+            other => add_seconds(other, seconds).ok_or_else(overflow)?,
+        };
+    }
+
+    Ok(value)
 }
 
 pub fn parse_order_clause(order_clause_pair: Pair<Rule>) -> Result<AnyClause, Error> {
@@ -359,18 +976,71 @@ pub fn parse_limit_clause(limit_clause_pair: Pair<Rule>) -> Result<AnyClause, Er
     Ok(AnyClause::Limit(LimitClause::new(amount)))
 }
 
-pub fn parse_insert_query<'a, K: DatabaseKey>(insert_query_pair: Pair<Rule>, database: &'a mut Database<K>) -> Result<AnyCommand<'a, K>, Error> {
-    let items: Vec<_> = insert_query_pair.into_inner().collect();
+pub fn parse_insert_query(insert_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let mut inner_rules = insert_query_pair.into_inner().skip(1).peekable();
 
-    let assign_list_pair = expect_rule(items.get(1).cloned(), Rule::assign_list, "Missing or invalid assignment list")?;
-    let table_ident_pair = expect_rule(items.get(3).cloned(), Rule::ident, "Missing or invalid table identifier")?;
+    let conflict_mode_pair = inner_rules.next_if(|pair| pair.as_rule() == Rule::conflict_mode);
+    let assign_list_pair = expect_rule(inner_rules.next(), Rule::assign_list, "Missing or invalid assignment list")?;
+    let table_ident_pair = expect_rule(inner_rules.nth(1), Rule::ident, "Missing or invalid table identifier")?;
+    let conflict_clause_pair = inner_rules.next_if(|pair| pair.as_rule() == Rule::conflict_clause);
+    let returning_clause_pair = possible_rule(inner_rules.next(), Rule::returning_clause, "Invalid returning clause")?;
 
+    let on_conflict = parse_on_conflict(conflict_mode_pair, conflict_clause_pair)?;
     let assign_list = parse_assign_list(assign_list_pair)?;
     let table_id = parse_ident(table_ident_pair)?;
+    let returning = returning_clause_pair.map(parse_returning_clause).transpose()?;
+
+    Ok(Statement::Insert { table: table_id, assignments: assign_list, on_conflict, returning })
+}
+
+pub fn parse_insert_select_query(insert_select_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let mut inner_rules = insert_select_query_pair.into_inner().skip(1).peekable();
+
+    let conflict_mode_pair = inner_rules.next_if(|pair| pair.as_rule() == Rule::conflict_mode);
+    let table_ident_pair = expect_rule(inner_rules.nth(1), Rule::ident, "Missing or invalid table identifier")?;
+    let select_pair = expect_rule(inner_rules.next(), Rule::select_query, "Missing or invalid select query")?;
+    let conflict_clause_pair = inner_rules.next_if(|pair| pair.as_rule() == Rule::conflict_clause);
+    let returning_clause_pair = possible_rule(inner_rules.next(), Rule::returning_clause, "Invalid returning clause")?;
+
+    let on_conflict = parse_on_conflict(conflict_mode_pair, conflict_clause_pair)?;
+    let table_id = parse_ident(table_ident_pair)?;
+    let select = parse_select_statement(select_pair)?;
+    let returning = returning_clause_pair.map(parse_returning_clause).transpose()?;
+
+    Ok(Statement::InsertSelect { table: table_id, select, on_conflict, returning })
+}
+
+pub fn parse_on_conflict(conflict_mode_pair: Option<Pair<Rule>>, conflict_clause_pair: Option<Pair<Rule>>) -> Result<OnConflict, Error> {
+    match (conflict_mode_pair, conflict_clause_pair) {
+        (None, None) => Ok(OnConflict::Fail),
+        (Some(_), Some(_)) => Err(Error::ParseError("OR REPLACE/OR IGNORE cannot be combined with ON CONFLICT".to_string())),
+        (Some(mode_pair), None) => {
+            let mode = expect_any_rule(mode_pair.into_inner().nth(1), "Expected REPLACE or IGNORE")?;
+
+            match mode.as_rule() {
+                Rule::REPLACE => Ok(OnConflict::Replace),
+                Rule::IGNORE => Ok(OnConflict::Ignore),
+                _ => Err(Error::UnknownTokenError(String::from("Unexpected token in conflict mode")))
+            }
+        },
+        (None, Some(clause_pair)) => {
+            let assign_list_pair = expect_rule(clause_pair.into_inner().nth(5), Rule::assign_list, "Missing or invalid assignment list")?;
+
+            let assign_list = parse_assign_list(assign_list_pair)?;
+
+            Ok(OnConflict::Update(assign_list))
+        },
+    }
+}
+
+pub fn parse_returning_clause(returning_clause_pair: Pair<Rule>) -> Result<Vec<String>, Error> {
+    let mut returning_clause = returning_clause_pair.into_inner();
+
+    let fields_pair = expect_rule(returning_clause.nth(1), Rule::ident_list, "Missing or invalid field list")?;
 
-    let table = database.get_table(&table_id)?;
+    let fields = parse_ident_list(fields_pair)?;
 
-    Ok(AnyCommand::Insert(InsertCommand::new(table, assign_list)))
+    Ok(fields)
 }
 
 pub fn parse_assign_list(assign_list_pair: Pair<Rule>) -> Result<Vec<(String, IntermediateValue)>, Error> {
@@ -396,34 +1066,56 @@ pub fn parse_assign(assign_pair: Pair<Rule>) -> Result<(String, IntermediateValu
     Ok((key, value))
 }
 
-pub fn parse_delete_query<'a, K: DatabaseKey>(delete_query_pair: Pair<Rule>, database: &'a mut Database<K>) -> Result<AnyCommand<'a, K>, Error> {
+pub fn parse_delete_query(delete_query_pair: Pair<Rule>) -> Result<Statement, Error> {
     let items: Vec<_> = delete_query_pair.into_inner().collect();
 
-    let key_type_pair = expect_rule(items.get(1).cloned(), Rule::key_type_def, "Missing or invalid key type")?;
+    let key_type_pair = expect_any_rule(items.get(1).cloned(), "Missing or invalid key type")?;
     let table_ident_pair = expect_rule(items.get(3).cloned(), Rule::ident, "Missing or invalid table identifier")?;
+    let returning_clause_pair = possible_rule(items.get(4).cloned(), Rule::returning_clause, "Invalid returning clause")?;
 
-    let key = parse_key_type_def(key_type_pair)?;
+    let key = match key_type_pair.as_rule() {
+        Rule::key_tuple => key_type_pair.into_inner().map(parse_key_type_def).collect::<Result<Vec<_>, _>>()?,
+        _ => vec![parse_key_type_def(key_type_pair)?],
+    };
     let table_id = parse_ident(table_ident_pair)?;
+    let returning = returning_clause_pair.map(parse_returning_clause).transpose()?;
+
+    Ok(Statement::Delete { table: table_id, key, returning })
+}
+
+pub fn parse_explain_query(explain_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let mut inner_rules = explain_query_pair.into_inner().skip(1).peekable();
+
+    let analyze = inner_rules.next_if(|pair| pair.as_rule() == Rule::ANALYZE).is_some();
+    let select_pair = expect_rule(inner_rules.next(), Rule::select_query, "Missing or invalid select query")?;
+
+    let select = parse_select_statement(select_pair)?;
 
-    let table = database.get_table(&table_id)?;
+    Ok(Statement::Explain { select, analyze })
+}
+
+pub fn parse_describe_query(describe_query_pair: Pair<Rule>) -> Result<Statement, Error> {
+    let table_ident_pair = expect_rule(describe_query_pair.into_inner().nth(1), Rule::ident, "Missing or invalid table identifier")?;
 
-    Ok(AnyCommand::Delete(DeleteCommand::new(table, key)))
+    let table = parse_ident(table_ident_pair)?;
+
+    Ok(Statement::Describe { table })
 }
 
-pub fn parse_read_query<'a, K: DatabaseKey>(read_query_pair: Pair<Rule>, database: &'a mut Database<K>) -> Result<AnyCommand<'a, K>, Error> {
+pub fn parse_read_query(read_query_pair: Pair<Rule>) -> Result<Statement, Error> {
     let path_rule = expect_rule(read_query_pair.into_inner().nth(1), Rule::path, "Missing or invalid path")?;
 
     let path = parse_path(path_rule)?;
 
-    Ok(AnyCommand::Read(ReadCommand::new(database, path)))
+    Ok(Statement::Read { path })
 }
 
-pub fn parse_save_query<'a, K: DatabaseKey>(save_query_pair: Pair<Rule>, database: &'a mut Database<K>) -> Result<AnyCommand<'a, K>, Error> {
+pub fn parse_save_query(save_query_pair: Pair<Rule>) -> Result<Statement, Error> {
     let path_rule = expect_rule(save_query_pair.into_inner().nth(1), Rule::path, "Missing or invalid path")?;
 
     let path = parse_path(path_rule)?;
 
-    Ok(AnyCommand::Save(SaveCommand::new(path, database.get_session_commands())))
+    Ok(Statement::Save { path })
 }
 
 #[cfg(test)]
@@ -431,25 +1123,27 @@ mod tests {
     use std::fs;
     use crate::commands::command::ExecutionSuccessValue;
     use crate::database::databases::AnyDatabase;
+    use crate::database::ident::quote_ident;
+    use crate::database::key::AnyKey;
     use crate::database::value::Value;
     use super::*;
 
     #[test]
     fn test_parse_create_command() {
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
 
         let cmd = "CREATE library KEY id
             FIELDS id: String, year: Int";
 
         let result = db.execute_command(cmd);
 
-        assert!(matches!(result, Ok(_)));
+        assert!(result.is_ok());
         assert!(db.get_table(&"library".to_string()).is_ok());
     }
 
     #[test]
     fn test_parse_insert_command() {
-        let mut db = Database::<i64>::new();
+        let mut db = Database::<AnyKey>::new();
 
         let cmd = "CREATE library KEY id
         FIELDS id: Int, year: Int";
@@ -460,13 +1154,13 @@ mod tests {
 
         let result = db.execute_command(cmd);
 
-        assert!(matches!(result, Ok(_)));
+        assert!(result.is_ok());
         assert_eq!(db.get_table(&"library".to_string()).unwrap().len(), 1)
     }
 
     #[test]
     fn test_parse_delete_command() {
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
 
         let cmd = "CREATE library KEY id
         FIELDS id: String, year: Int";
@@ -481,13 +1175,13 @@ mod tests {
 
         let result = db.execute_command(cmd);
 
-        assert!(matches!(result, Ok(_)));
+        assert!(result.is_ok());
         assert_eq!(db.get_table(&"library".to_string()).unwrap().len(), 0)
     }
 
     #[test]
     fn test_parse_select_command() {
-        let mut db = Database::<i64>::new();
+        let mut db = Database::<AnyKey>::new();
 
         let cmd = "CREATE library KEY id
         FIELDS id: Int, year: Int";
@@ -510,7 +1204,7 @@ mod tests {
 
         let result = db.execute_command(cmd);
 
-        assert!(matches!(result, Ok(_)));
+        assert!(result.is_ok());
         if let Ok(ExecutionSuccessValue::SelectResult(r)) = result {
             assert_eq!(r.rows.len(), 3);
         }
@@ -518,7 +1212,7 @@ mod tests {
 
     #[test]
     fn test_parse_select_where_command() {
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
 
         let cmd = "CREATE library KEY id
         FIELDS id: String, year: Int";
@@ -541,7 +1235,7 @@ mod tests {
 
         let result = db.execute_command(cmd);
 
-        assert!(matches!(result, Ok(_)));
+        assert!(result.is_ok());
         if let Ok(ExecutionSuccessValue::SelectResult(r)) = result {
             assert_eq!(r.rows.len(), 2);
         }
@@ -549,7 +1243,7 @@ mod tests {
 
     #[test]
     fn test_parse_select_order_command() {
-        let mut db = Database::<i64>::new();
+        let mut db = Database::<AnyKey>::new();
 
         let cmd = "CREATE library KEY id
         FIELDS id: Int, year: Int";
@@ -572,7 +1266,7 @@ mod tests {
 
         let result = db.execute_command(cmd);
 
-        assert!(matches!(result, Ok(_)));
+        assert!(result.is_ok());
         if let Ok(ExecutionSuccessValue::SelectResult(r)) = result {
             assert_eq!(r.rows.len(), 3);
             if let Value::Int(id) = &r.rows[0].values[0].1 {
@@ -589,7 +1283,7 @@ mod tests {
 
     #[test]
     fn test_parse_select_limit_command() {
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
 
         let cmd = "CREATE library KEY id
         FIELDS id: String, year: Int";
@@ -612,15 +1306,964 @@ mod tests {
 
         let result = db.execute_command(cmd);
 
-        assert!(matches!(result, Ok(_)));
+        assert!(result.is_ok());
+        if let Ok(ExecutionSuccessValue::SelectResult(r)) = result {
+            assert_eq!(r.rows.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_parse_insert_select_command() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE books KEY id
+        FIELDS id: Int, year: Int").unwrap();
+        db.execute_command("CREATE archive KEY id
+        FIELDS id: Int, year: Int").unwrap();
+
+        db.execute_command("INSERT id = 1, year = 1985 INTO books").unwrap();
+        db.execute_command("INSERT id = 2, year = 1999 INTO books").unwrap();
+
+        let result = db.execute_command("INSERT INTO archive SELECT id, year FROM books WHERE year < 1990");
+
+        assert!(result.is_ok());
+        assert_eq!(db.get_table(&"archive".to_string()).unwrap().len(), 1)
+    }
+
+    #[test]
+    fn test_parse_create_select_command() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE books KEY id
+        FIELDS id: Int, year: Int").unwrap();
+
+        db.execute_command("INSERT id = 1, year = 1985 INTO books").unwrap();
+        db.execute_command("INSERT id = 2, year = 1999 INTO books").unwrap();
+
+        let cmd = "CREATE archive KEY id AS SELECT id, year FROM books WHERE year < 1990";
+
+        assert!(is_complete_command(cmd));
+
+        let result = db.execute_command(cmd);
+
+        assert!(result.is_ok());
+        assert_eq!(db.get_table(&"archive".to_string()).unwrap().len(), 1)
+    }
+
+    #[test]
+    fn test_parse_insert_conflict_commands() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE library KEY id
+        FIELDS id: Int, year: Int").unwrap();
+
+        db.execute_command("INSERT id = 1, year = 2000 INTO library").unwrap();
+
+        let result = db.execute_command("INSERT id = 1, year = 2001 INTO library");
+        assert!(matches!(result, Err(Error::AlreadyExistsError(_))));
+
+        let result = db.execute_command("INSERT OR IGNORE id = 1, year = 2001 INTO library").unwrap();
+        assert_eq!(result.to_string().trim_end(), "Inserted 0, replaced 0, updated 0 and skipped 1 records");
+
+        let result = db.execute_command("INSERT OR REPLACE id = 1, year = 2002 INTO library").unwrap();
+        assert_eq!(result.to_string().trim_end(), "Inserted 0, replaced 1, updated 0 and skipped 0 records");
+
+        let result = db.execute_command("INSERT id = 1, year = 2000 INTO library ON CONFLICT DO UPDATE SET year = 2003").unwrap();
+        assert_eq!(result.to_string().trim_end(), "Inserted 0, replaced 0, updated 1 and skipped 0 records");
+
+        let result = db.execute_command("SELECT year FROM library WHERE year = 2003");
         if let Ok(ExecutionSuccessValue::SelectResult(r)) = result {
             assert_eq!(r.rows.len(), 1);
+        } else {
+            panic!("Expected a select result");
         }
+
+        let result = db.execute_command("INSERT OR REPLACE id = 1, year = 2000 INTO library ON CONFLICT DO UPDATE SET year = 2003");
+        assert!(matches!(result, Err(Error::ParseError(_))));
+    }
+
+    #[test]
+    fn test_parse_returning_commands() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE library KEY id
+        FIELDS id: Int, year: Int").unwrap();
+
+        let result = db.execute_command("INSERT id = 1, year = 2000 INTO library RETURNING id, year");
+        if let Ok(ExecutionSuccessValue::SelectResult(r)) = result {
+            assert_eq!(r.rows.len(), 1);
+            assert_eq!(r.rows[0].to_string(), "id: 1, year: 2000");
+        } else {
+            panic!("Expected a select result");
+        }
+
+        let result = db.execute_command("INSERT id = 1, year = 2000 INTO library ON CONFLICT DO UPDATE SET year = 2001 RETURNING year");
+        if let Ok(ExecutionSuccessValue::SelectResult(r)) = result {
+            assert_eq!(r.rows[0].to_string(), "year: 2001");
+        } else {
+            panic!("Expected a select result");
+        }
+
+        let result = db.execute_command("DELETE 1 FROM library RETURNING id, missing");
+        assert!(matches!(result, Err(Error::MissingFieldError(_))));
+        assert_eq!(db.get_table(&"library".to_string()).unwrap().len(), 1);
+
+        let result = db.execute_command("DELETE 1 FROM library RETURNING id");
+        if let Ok(ExecutionSuccessValue::SelectResult(r)) = result {
+            assert_eq!(r.rows[0].to_string(), "id: 1");
+        } else {
+            panic!("Expected a select result");
+        }
+        assert_eq!(db.get_table(&"library".to_string()).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn test_parse_explain_command() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE library KEY id
+        FIELDS id: Int, year: Int").unwrap();
+
+        db.execute_command("INSERT id = 1, year = 2000 INTO library").unwrap();
+        db.execute_command("INSERT id = 2, year = 2001 INTO library").unwrap();
+
+        let result = db.execute_command("EXPLAIN SELECT id FROM library WHERE year > 2000 OR (id = 1 AND year != 1990) ORDER_BY year, id LIMIT 5");
+        if let Ok(ExecutionSuccessValue::SelectResult(r)) = result {
+            assert_eq!(r.rows.len(), 5);
+            assert_eq!(r.rows[1].to_string(), "stage: \"Filter\", detail: \"(year > 2000 OR (id = 1 AND year != 1990))\"");
+            assert_eq!(r.rows[2].to_string(), "stage: \"Sort\", detail: \"year, id\"");
+        } else {
+            panic!("Expected a select result");
+        }
+
+        let result = db.execute_command("EXPLAIN ANALYZE SELECT id FROM library WHERE year > 2000");
+        if let Ok(ExecutionSuccessValue::SelectResult(r)) = result {
+            assert_eq!(r.rows.len(), 3);
+            assert!(matches!(r.rows[1].values[3].1, Value::Int(1)));
+        } else {
+            panic!("Expected a select result");
+        }
+    }
+
+    #[test]
+    fn test_parse_show_tables_and_describe_commands() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE library KEY id
+        FIELDS id: Int, year: Int").unwrap();
+        db.execute_command("INSERT id = 1, year = 2000 INTO library").unwrap();
+
+        db.execute_command("CREATE VIEW recent AS SELECT id, year FROM library WHERE year > 1990").unwrap();
+        db.execute_command("CREATE MATERIALIZED VIEW archive AS SELECT id, year FROM library").unwrap();
+
+        let result = db.execute_command("SHOW TABLES").unwrap();
+        assert_eq!(result.to_string(), "name: \"archive\", key: \"id\", fields: \"id: Int, year: Int\", rows: 1, kind: \"materialized view\"
+name: \"library\", key: \"id\", fields: \"id: Int, year: Int\", rows: 1, kind: \"table\"
+name: \"recent\", key: \"\", fields: \"id: Int, year: Int\", rows: 0, kind: \"view\"
+");
+
+        let result = db.execute_command("DESCRIBE library").unwrap();
+        assert_eq!(result.affected_rows(), 2);
+
+        let result = db.execute_command("DESCRIBE recent").unwrap();
+        assert_eq!(result.to_string(), "field: \"id\", type: \"Int\", key: false, nullable: false, rows: 1
+field: \"year\", type: \"Int\", key: false, nullable: false, rows: 1
+");
+
+        let result = db.execute_command("DESCRIBE archive").unwrap();
+        assert!(result.to_string().starts_with("field: \"id\", type: \"Int\", key: true"));
+
+        let result = db.execute_command("DESCRIBE sys_tables").unwrap();
+        assert_eq!(result.affected_rows(), 5);
+
+        let result = db.execute_command("DESCRIBE missing");
+        assert!(matches!(result, Err(Error::NotExistError(_))));
+    }
+
+    #[test]
+    fn test_parse_catalog_select() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE library KEY id
+        FIELDS id: Int, year: Int").unwrap();
+        db.execute_command("CREATE empty KEY id
+        FIELDS id: Int").unwrap();
+        db.execute_command("INSERT id = 1, year = 2000 INTO library").unwrap();
+
+        let result = db.execute_command("SELECT name FROM sys_tables WHERE rows > 0").unwrap();
+        assert_eq!(result.to_string(), "name: \"library\"\n");
+
+        let result = db.execute_command("SELECT field, type FROM sys_fields WHERE table_name = \"library\"").unwrap();
+        assert_eq!(result.to_string(), "field: \"id\", type: \"Int\"\nfield: \"year\", type: \"Int\"\n");
+
+        let result = db.execute_command("INSERT name = \"x\" INTO sys_tables");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("CREATE sys_fields KEY id
+        FIELDS id: Int");
+        assert!(matches!(result, Err(Error::AlreadyExistsError(_))));
+
+        db.execute_command("CREATE sys_log KEY id
+        FIELDS id: Int").unwrap();
+        db.execute_command("INSERT id = 1 INTO sys_log").unwrap();
+    }
+
+    #[test]
+    fn test_parse_view_commands() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE books KEY id
+        FIELDS id: Int, year: Int").unwrap();
+        db.execute_command("INSERT id = 1, year = 1985 INTO books").unwrap();
+        db.execute_command("INSERT id = 2, year = 1999 INTO books").unwrap();
+        db.execute_command("CREATE VIEW old_books AS SELECT id, year FROM books WHERE year < 1990").unwrap();
+        db.execute_command("INSERT id = 3, year = 1970 INTO books").unwrap();
+
+        let result = db.execute_command("SELECT id FROM old_books ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 1\nid: 3\n");
+
+        let result = db.execute_command("INSERT id = 4, year = 1960 INTO old_books");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        db.execute_command("CREATE VIEW oldest_books AS SELECT id FROM old_books WHERE year < 1980").unwrap();
+
+        let result = db.execute_command("SHOW TABLES").unwrap();
+        assert_eq!(result.to_string(), "name: \"books\", key: \"id\", fields: \"id: Int, year: Int\", rows: 3, kind: \"table\"
+name: \"old_books\", key: \"\", fields: \"id: Int, year: Int\", rows: 0, kind: \"view\"
+name: \"oldest_books\", key: \"\", fields: \"id: Int\", rows: 0, kind: \"view\"
+");
+
+        let result = db.execute_command("SELECT field, type FROM sys_fields WHERE table_name = \"oldest_books\"").unwrap();
+        assert_eq!(result.to_string(), "field: \"id\", type: \"Int\"\n");
+
+        let result = db.execute_command("DROP VIEW old_books");
+        assert!(matches!(&result, Err(Error::ConstraintViolationError(message)) if message.contains("'oldest_books'")));
+
+        db.execute_command("SAVE_AS parse_view_test_output.txt").unwrap();
+        db.execute_command("DROP VIEW oldest_books").unwrap();
+        db.execute_command("DROP VIEW old_books").unwrap();
+
+        let result = db.execute_command("SELECT id FROM old_books");
+        assert!(matches!(result, Err(Error::NotExistError(_))));
+
+        let mut restored = Database::<AnyKey>::new();
+        restored.execute_command("READ_FROM parse_view_test_output.txt").unwrap();
+
+        let result = restored.execute_command("SELECT id FROM old_books ORDER_BY id").unwrap();
+        assert_eq!(result.affected_rows(), 2);
+
+        fs::remove_file("parse_view_test_output.txt").unwrap();
+    }
+
+    #[test]
+    fn test_parse_materialized_view_commands() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE books KEY id
+        FIELDS id: Int, year: Int").unwrap();
+        db.execute_command("INSERT id = 1, year = 1985 INTO books").unwrap();
+        db.execute_command("CREATE MATERIALIZED VIEW old_books AS SELECT id, year FROM books WHERE year < 1990").unwrap();
+        db.execute_command("CREATE MATERIALIZED VIEW by_year KEY year AS SELECT year FROM old_books").unwrap();
+        db.execute_command("INSERT id = 2, year = 1970 INTO books").unwrap();
+
+        let result = db.execute_command("SELECT id FROM old_books").unwrap();
+        assert_eq!(result.affected_rows(), 1);
+
+        db.execute_command("REFRESH old_books").unwrap();
+        db.execute_command("REFRESH by_year").unwrap();
+
+        let result = db.execute_command("SELECT year FROM by_year ORDER_BY year").unwrap();
+        assert_eq!(result.to_string(), "year: 1970\nyear: 1985\n");
+
+        let result = db.execute_command("REFRESH books");
+        assert!(matches!(result, Err(Error::NotExistError(_))));
+    }
+
+    #[test]
+    fn test_parse_error_diagnostics() {
+        let mut db = Database::<AnyKey>::new();
+
+        let result = db.execute_command("CREATE books KEY id
+        FIELDS id: Int, year: Integr");
+        if let Err(Error::SyntaxError(diagnostic)) = result {
+            assert_eq!((diagnostic.line, diagnostic.column), (2, 31));
+            assert_eq!(diagnostic.message, "Expected a type such as Int, Float, String, Bool, Date or Decimal(10, 2) but found 'Integr'");
+        } else {
+            panic!("Expected a syntax error");
+        }
+
+        let result = db.execute_command("SELECT id FROM books LIMT 3");
+        if let Err(error) = result {
+            assert_eq!(error.to_string(), "SyntaxError: Expected 'WHERE', 'ORDER_BY', 'LIMIT' or end of input but found 'LIMT' at line 1, column 22
+  SELECT id FROM books LIMT 3
+                       ^^^^");
+        } else {
+            panic!("Expected a syntax error");
+        }
+
+        let result = db.execute_command("SELECT id FROM books WHERE year >");
+        assert!(matches!(result, Err(Error::SyntaxError(Diagnostic { column: 34, .. }))));
+
+        let result = db.execute_command("SELECT id FROM books WHERE year ~ 3");
+        if let Err(Error::SyntaxError(diagnostic)) = result {
+            assert!(diagnostic.message.starts_with("Expected a comparison operator (=, !=, <, <=, >, >=, CONTAINS or IS NULL) but found '~'"), "{}", diagnostic.message);
+        } else {
+            panic!("Expected a syntax error");
+        }
+    }
+
+    #[test]
+    fn test_parse_quoted_identifiers() {
+        let mut db = AnyDatabase::new();
+
+        let result = db.execute_command("CREATE `order-items` KEY _id
+        FIELDS [[_id]]: Int, `KEY`: String, `año`: Int");
+        assert!(matches!(result, Err(Error::SyntaxError(_))));
+        db.execute_command("CREATE `order-items` KEY `_id`
+        FIELDS [[_id]]: Int, `KEY`: String, `año`: Int").unwrap();
+        db.execute_command("INSERT `_id` = 1, `KEY` = \"a\", `año` = 2000 INTO `order-items`").unwrap();
+        db.execute_command("INSERT [[_id]] = 2, [[KEY]] = \"b\", [[año]] = 1990 INTO [[order-items]]").unwrap();
+
+        let result = db.execute_command("SELECT `KEY` FROM `order-items` WHERE `año` > 1995").unwrap();
+        assert_eq!(result.to_string(), "KEY: \"a\"\n");
+
+        let result = db.execute_command("EXPLAIN SELECT `_id` FROM `order-items` WHERE `año` > 1995 ORDER_BY `KEY`").unwrap();
+        assert!(result.to_string().contains("`año` > 1995"));
+        assert!(result.to_string().contains("detail: \"KEY\""));
+
+        db.execute_command("SAVE_AS parse_quoted_test_output.txt").unwrap();
+
+        let mut restored = Database::<AnyKey>::new();
+        restored.execute_command("READ_FROM parse_quoted_test_output.txt").unwrap();
+        assert_eq!(restored.get_table(&"order-items".to_string()).unwrap().len(), 2);
+
+        fs::remove_file("parse_quoted_test_output.txt").unwrap();
+    }
+
+    #[test]
+    fn test_quote_ident_matches_grammar() {
+        assert_eq!(quote_ident("year"), "year");
+        assert_eq!(quote_ident("FROM"), "FROM");
+        assert_eq!(quote_ident("_id"), "`_id`");
+        assert_eq!(quote_ident("odd`name"), "`odd``name`");
+        assert_eq!(quote_ident("a`b]]c"), "`a``b]]c`");
+
+        for name in ["_id", "año", "odd`name", "a`b]]c", "ends`]", "``"] {
+            let command = format!("SELECT {} FROM {}", quote_ident(name), quote_ident(name));
+            let Ok((Statement::Select(select), _, _)) = parse_statement(&command) else { panic!("{} does not parse", command) };
+
+            assert_eq!(select.table, name);
+            assert_eq!(select.fields[0].field, name);
+        }
+
+        let grammar_keywords = include_str!("commands.pest")
+            .lines()
+            .filter_map(|line| line.split_once(" = @{ \""))
+            .filter(|(name, _)| name.chars().all(|c| c.is_ascii_uppercase() || c == '_'))
+            .flat_map(|(_, rest)| {
+                let (word, rest) = rest.split_once('"').unwrap();
+                let plural = rest.trim_start().starts_with("~ \"S\"?").then(|| format!("{}S", word));
+                [Some(word.to_string()), plural].into_iter().flatten()
+            })
+            .collect::<Vec<_>>();
+
+        assert!(grammar_keywords.len() > 50);
+
+        let templates = [
+            "CREATE {} KEY {} AUTO\nFIELDS {}: Int, v: Int UNIQUE REFERENCES {} ON DELETE CASCADE, CONSTRAINT {} CHECK ({} > 1)",
+            "INSERT {} = 1 INTO {} ON CONFLICT DO UPDATE SET {} = 2 RETURNING {}",
+            "INSERT INTO {} SELECT {}, {}->\"a\" FROM {} WHERE {} = 1 AND LENGTH({}) > 2 ORDER_BY {} LIMIT 1",
+            "CREATE MATERIALIZED VIEW {} KEY {} AS SELECT {} FROM {}",
+            "DELETE 1 FROM {} RETURNING {}",
+        ];
+
+        for keyword in &grammar_keywords {
+            for template in templates {
+                let bare = parse_statement(&template.replace("{}", keyword)).map(|(statement, _, _)| format!("{:?}", statement));
+                let quoted = parse_statement(&template.replace("{}", &format!("`{}`", keyword))).map(|(statement, _, _)| format!("{:?}", statement));
+
+                assert_eq!(bare.ok(), Some(quoted.unwrap()), "{} cannot be used as a bare name in {:?}", keyword, template);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_date_and_timestamp() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE events KEY id
+        FIELDS id: Int, day: Date, at: Timestamp").unwrap();
+        db.execute_command("INSERT id = 1, day = DATE \"2024-01-31\", at = TIMESTAMP \"2024-01-31 10:00:00\" INTO events").unwrap();
+        db.execute_command("INSERT id = 2, day = DATE \"2023-12-24\" + 7 DAYS, at = DATE \"2023-12-31\" - 90 MINUTES INTO events").unwrap();
+
+        let clock = parse_timestamp("2024-06-15 12:00:00").unwrap();
+        let insert = prepare_command("INSERT id = 3, day = TODAY() - 1 DAYS, at = NOW() + 30 MINUTES INTO events").unwrap();
+        db.execute_statement(insert.bind(&[], clock).unwrap()).unwrap();
+
+        let result = db.execute_command("SELECT id, day, at FROM events WHERE day < DATE \"2025-01-01\" ORDER_BY day").unwrap();
+        assert_eq!(result.to_string(), "id: 2, day: 2023-12-31, at: 2023-12-30 22:30:00
+id: 1, day: 2024-01-31, at: 2024-01-31 10:00:00
+id: 3, day: 2024-06-14, at: 2024-06-15 12:30:00
+");
+
+        let result = db.execute_command("SELECT id FROM events WHERE YEAR(day) = 2024 AND HOUR(at) >= 10").unwrap();
+        assert_eq!(result.affected_rows(), 2);
+
+        let select = prepare_command("SELECT id FROM events WHERE at >= NOW() - 1 DAYS").unwrap();
+        let result = db.execute_statement(select.bind(&[], clock).unwrap()).unwrap();
+        assert_eq!(result.to_string(), "id: 3\n");
+        assert_eq!(select.render(&[], clock).unwrap(), "SELECT id FROM events WHERE at >= TIMESTAMP \"2024-06-14 12:00:00\"");
+
+        let result = db.execute_statement(select.bind(&[], clock + 2 * SECONDS_PER_DAY).unwrap()).unwrap();
+        assert_eq!(result.affected_rows(), 0);
+
+        let result = db.execute_command("INSERT id = 4, day = DATE \"2023-02-29\", at = NOW() INTO events");
+        assert!(matches!(result, Err(Error::SyntaxError(Diagnostic { column: 28, .. }))));
+
+        let result = db.execute_command("INSERT id = 4, day = \"2023-02-28\", at = NOW() INTO events");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE orders KEY id
+        FIELDS id: Int, total: Decimal(10, 2), rate: Float").unwrap();
+        db.execute_command("INSERT id = 1, total = 0.10, rate = 0.5 INTO orders").unwrap();
+        db.execute_command("INSERT id = 2, total = 5, rate = 1.25 INTO orders").unwrap();
+        db.execute_command("INSERT id = 3, total = 0.20, rate = 1 INTO orders").unwrap();
+
+        let result = db.execute_command("SELECT id, total FROM orders WHERE total > 0.1 ORDER_BY total").unwrap();
+        assert_eq!(result.to_string(), "id: 3, total: 0.20\nid: 2, total: 5.00\n");
+
+        let result = db.execute_command("SELECT id FROM orders WHERE total = 0.1").unwrap();
+        assert_eq!(result.to_string(), "id: 1\n");
+
+        let result = db.execute_command("INSERT id = 4, total = 1.005, rate = 1 INTO orders");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("INSERT id = 4, total = 123456789.00, rate = 1 INTO orders");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        db.execute_command("INSERT id = 4, total = 19.99 * 3 + 0.03, rate = 0.1 INTO orders").unwrap();
+        db.execute_command("INSERT id = 5, total = 10 - 2 * 3, rate = 0.1234567890123456789012345678901234567890 INTO orders").unwrap();
+
+        let result = db.execute_command("SELECT id, total FROM orders WHERE total >= 0.1 * 3 - 0.2 ORDER_BY total LIMIT 1").unwrap();
+        assert_eq!(result.to_string(), "id: 1, total: 0.10\n");
+
+        let result = db.execute_command("SELECT id, total FROM orders WHERE total = 60").unwrap();
+        assert_eq!(result.to_string(), "id: 4, total: 60.00\n");
+
+        let result = db.execute_command("SELECT id, total FROM orders WHERE total = 4").unwrap();
+        assert_eq!(result.to_string(), "id: 5, total: 4.00\n");
+
+        let result = db.execute_command("SELECT id FROM orders WHERE rate = 0.1").unwrap();
+        assert_eq!(result.to_string(), "id: 4\n");
+
+        let result = db.execute_command("SELECT id FROM orders WHERE rate = 0.12345678901234567890123456789012345678901").unwrap();
+        assert_eq!(result.to_string(), "id: 5\n");
+
+        let result = db.execute_command("INSERT id = 6, total = 99999999999999999999.99 * 99999999999999999999.99, rate = 1 INTO orders");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("CREATE invalid KEY id
+        FIELDS id: Int, total: Decimal(2, 3)");
+        assert!(matches!(result, Err(Error::SyntaxError(_))));
+    }
+
+    #[test]
+    fn test_parse_large_integers() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE users KEY id
+        FIELDS id: Int, external: Int").unwrap();
+        db.execute_command("INSERT id = 9007199254740993, external = 9223372036854775807 INTO users").unwrap();
+        db.execute_command("INSERT id = 9007199254740992, external = -9223372036854775808 INTO users").unwrap();
+
+        let result = db.execute_command("SELECT id, external FROM users WHERE id = 9007199254740993").unwrap();
+        assert_eq!(result.to_string(), "id: 9007199254740993, external: 9223372036854775807\n");
+
+        let result = db.execute_command("SELECT id FROM users WHERE id > 9007199254740992").unwrap();
+        assert_eq!(result.affected_rows(), 1);
+
+        db.execute_command("DELETE 9007199254740993 FROM users").unwrap();
+        assert_eq!(db.get_table(&"users".to_string()).unwrap().len(), 1);
+
+        let result = db.execute_command("INSERT id = 1, external = 9223372036854775808 INTO users");
+        assert!(matches!(result, Err(Error::SyntaxError(_))));
+
+        let result = db.execute_command("DELETE 99999999999999999999 FROM users");
+        assert!(matches!(result, Err(Error::SyntaxError(_))));
+    }
+
+    #[test]
+    fn test_parse_bytes_and_uuid() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE files KEY id
+        FIELDS id: Uuid, hash: Bytes").unwrap();
+        db.execute_command("INSERT id = \"123e4567-e89b-12d3-a456-426614174000\", hash = x\"DEADbeef\" INTO files").unwrap();
+        db.execute_command("INSERT id = \"00000000-0000-0000-0000-000000000001\", hash = x\"\" INTO files").unwrap();
+
+        let result = db.execute_command("SELECT id, hash FROM files WHERE hash > x\"00\"").unwrap();
+        assert_eq!(result.to_string(), "id: 123e4567-e89b-12d3-a456-426614174000, hash: x\"deadbeef\"\n");
+
+        let result = db.execute_command("SELECT hash FROM files WHERE id = \"00000000-0000-0000-0000-000000000001\"").unwrap();
+        assert_eq!(result.to_string(), "hash: x\"\"\n");
+
+        let result = db.execute_command("INSERT id = \"not-a-uuid\", hash = x\"00\" INTO files");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("INSERT id = \"00000000-0000-0000-0000-000000000002\", hash = x\"abc\" INTO files");
+        assert!(matches!(result, Err(Error::SyntaxError(_))));
+
+        let result = db.execute_command("INSERT id = \"00000000-0000-0000-0000-000000000002\", hash = x\"0g\" INTO files");
+        assert!(matches!(result, Err(Error::SyntaxError(Diagnostic { ref message, .. })) if message == "Invalid hex digit 'g' in '0g'"));
+
+        let result = db.execute_command("INSERT id = \"00000000-0000-0000-0000-00000000000g\", hash = x\"00\" INTO files");
+        assert!(matches!(result, Err(Error::TypeError(ref message)) if message.ends_with("'g' is not a hex digit")));
+
+        db.execute_command("SAVE_AS parse_uuid_test_output.txt").unwrap();
+
+        let mut restored = Database::<AnyKey>::new();
+        restored.execute_command("READ_FROM parse_uuid_test_output.txt").unwrap();
+        restored.execute_command("DELETE \"123e4567-e89b-12d3-a456-426614174000\" FROM files").unwrap();
+        assert_eq!(restored.get_table(&"files".to_string()).unwrap().len(), 1);
+
+        fs::remove_file("parse_uuid_test_output.txt").unwrap();
+    }
+
+    #[test]
+    fn test_parse_lists() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE posts KEY id
+        FIELDS id: Int, title: String, tags: List<String>").unwrap();
+        db.execute_command("INSERT id = 1, title = \"Hello\", tags = [\"rust\", \"db\"] INTO posts").unwrap();
+        db.execute_command("INSERT id = 2, title = \"Empty\", tags = [] INTO posts").unwrap();
+
+        let result = db.execute_command("SELECT id, tags FROM posts WHERE tags CONTAINS \"rust\"").unwrap();
+        assert_eq!(result.to_string(), "id: 1, tags: [\"rust\", \"db\"]\n");
+
+        let result = db.execute_command("SELECT id FROM posts WHERE LENGTH(tags) = 0").unwrap();
+        assert_eq!(result.to_string(), "id: 2\n");
+
+        let result = db.execute_command("SELECT id FROM posts WHERE LENGTH(title) > 4 ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 1\nid: 2\n");
+
+        let result = db.execute_command("INSERT id = 3, title = \"Bad\", tags = [1, 2] INTO posts");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("SELECT id FROM posts WHERE title CONTAINS \"H\"");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE events KEY id
+        FIELDS id: Int, payload: Json").unwrap();
+        db.execute_command("INSERT id = 1, payload = JSON '{\"user\": {\"id\": 7, \"name\": \"Ann\"}, \"tags\": [\"a\", \"b\"]}' INTO events").unwrap();
+        db.execute_command("INSERT id = 2, payload = JSON '{\"user\": {\"id\": 9}}' INTO events").unwrap();
+
+        let result = db.execute_command("SELECT id, payload->\"user\"->\"name\" FROM events ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 1, payload->\"user\"->\"name\": \"Ann\"\nid: 2, payload->\"user\"->\"name\": null\n");
+
+        let result = db.execute_command("SELECT id FROM events WHERE payload -> \"user\" -> \"id\" > 8").unwrap();
+        assert_eq!(result.to_string(), "id: 2\n");
+
+        let result = db.execute_command("SELECT id FROM events WHERE payload->\"user\"->\"name\" = \"Ann\"").unwrap();
+        assert_eq!(result.to_string(), "id: 1\n");
+
+        let result = db.execute_command("SELECT id FROM events WHERE payload->\"tags\" CONTAINS \"b\" AND LENGTH(payload) = 2").unwrap();
+        assert_eq!(result.to_string(), "id: 1\n");
+
+        let result = db.execute_command("SELECT id FROM events WHERE payload->\"user\" = JSON '{\"id\": 9.0}'").unwrap();
+        assert_eq!(result.to_string(), "id: 2\n");
+
+        let result = db.execute_command("SELECT id FROM events WHERE payload->\"user\"->\"id\" = \"7\"");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("INSERT id = 3, payload = JSON '{\"user\": }' INTO events");
+        assert!(matches!(result, Err(Error::SyntaxError(_))));
+
+        let insert = db.prepare("INSERT id = ?, payload = ? INTO events").unwrap();
+        let result = db.execute_prepared(&insert, &[IntermediateValue::Int(3), IntermediateValue::String("[1, 2".to_string())]);
+        assert!(matches!(result, Err(Error::TypeError(_))));
+    }
+
+    #[test]
+    fn test_parse_enum() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE orders KEY id
+        FIELDS id: Int, status: Enum(\"new\", \"paid\", \"shipped\")").unwrap();
+        db.execute_command("INSERT id = 1, status = \"shipped\" INTO orders").unwrap();
+        db.execute_command("INSERT id = 2, status = \"new\" INTO orders").unwrap();
+        db.execute_command("INSERT id = 3, status = \"paid\" INTO orders").unwrap();
+
+        let result = db.execute_command("INSERT id = 4, status = \"lost\" INTO orders");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("SELECT id, status FROM orders ORDER_BY status").unwrap();
+        assert_eq!(result.to_string(), "id: 2, status: \"new\"\nid: 3, status: \"paid\"\nid: 1, status: \"shipped\"\n");
+
+        let result = db.execute_command("SELECT id FROM orders WHERE status >= \"paid\" ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 1\nid: 3\n");
+
+        let result = db.execute_command("SELECT id FROM orders WHERE status = \"lost\"");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("CREATE broken KEY id
+        FIELDS id: Int, status: Enum(\"new\", \"new\")");
+        assert!(matches!(result, Err(Error::SyntaxError(_))));
+
+        db.execute_command("SAVE_AS parse_enum_test_output.txt").unwrap();
+
+        let mut restored = Database::<AnyKey>::new();
+        restored.execute_command("READ_FROM parse_enum_test_output.txt").unwrap();
+
+        let result = restored.execute_command("SELECT type FROM sys_fields WHERE table_name = \"orders\" AND field = \"status\"").unwrap();
+        assert_eq!(result.to_string(), "type: \"Enum(\"new\", \"paid\", \"shipped\")\"\n");
+
+        let result = restored.execute_command("INSERT id = 4, status = \"lost\" INTO orders");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        fs::remove_file("parse_enum_test_output.txt").unwrap();
+    }
+
+    #[test]
+    fn test_parse_composite_key() {
+        let mut db = Database::<AnyKey>::new();
+
+        db.execute_command("CREATE order_lines KEY (order_id, line_no)
+        FIELDS order_id: Int, line_no: Int, qty: Int").unwrap();
+        db.execute_command("INSERT order_id = 1, line_no = 1, qty = 5 INTO order_lines").unwrap();
+        db.execute_command("INSERT order_id = 1, line_no = 2, qty = 3 INTO order_lines").unwrap();
+        db.execute_command("INSERT order_id = 2, line_no = 1, qty = 1 INTO order_lines").unwrap();
+
+        let result = db.execute_command("INSERT order_id = 1, line_no = 2, qty = 9 INTO order_lines");
+        assert!(matches!(result, Err(Error::AlreadyExistsError(_))));
+
+        let result = db.execute_command("SHOW TABLES").unwrap();
+        assert_eq!(result.to_string(), "name: \"order_lines\", key: \"order_id, line_no\", fields: \"order_id: Int, line_no: Int, qty: Int\", rows: 3, kind: \"table\"\n");
+
+        let result = db.execute_command("DELETE (1, 2) FROM order_lines").unwrap();
+        assert_eq!(result.to_string(), "Successfully deleted record (1, 2) from table\n");
+
+        let result = db.execute_command("DELETE 1 FROM order_lines");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let delete = db.prepare("DELETE (?, ?) FROM order_lines RETURNING qty").unwrap();
+        let result = db.execute_prepared(&delete, &[IntermediateValue::Int(2), IntermediateValue::Int(1)]).unwrap();
+        assert_eq!(result.to_string(), "qty: 1\n");
+
+        let result = db.execute_command("SELECT line_no, qty FROM order_lines").unwrap();
+        assert_eq!(result.to_string(), "line_no: 1, qty: 5\n");
+
+        let result = db.execute_command("INSERT order_id = 1, line_no = 1, qty = 7 INTO order_lines ON CONFLICT DO UPDATE SET line_no = 3");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("CREATE broken KEY (order_id, order_id)
+        FIELDS order_id: Int");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        db.execute_command("INSERT order_id = 3, line_no = 1, qty = 2 INTO order_lines").unwrap();
+
+        let result = db.execute_command("CREATE big_lines KEY (order_id, line_no) AS SELECT order_id, line_no, qty FROM order_lines WHERE qty > 1").unwrap();
+        assert_eq!(result.to_string(), "Table 'big_lines' created successfully with 2 records\n");
+        db.execute_command("DELETE (3, 1) FROM big_lines").unwrap();
+
+        db.execute_command("CREATE MATERIALIZED VIEW line_totals KEY (line_no, order_id) AS SELECT order_id, line_no, qty FROM order_lines").unwrap();
+        let result = db.execute_command("DESCRIBE line_totals").unwrap();
+        assert!(result.to_string().starts_with("field: \"line_no\", type: \"Int\", key: true"), "{}", result);
+
+        let result = db.execute_command("CREATE MATERIALIZED VIEW broken KEY (qty, qty) AS SELECT order_id, line_no, qty FROM order_lines");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+    }
+
+    #[test]
+    fn test_parse_auto_increment() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE notes KEY id AUTO
+        FIELDS id: Int, text: String").unwrap();
+
+        let result = db.execute_command("INSERT text = \"a\" INTO notes").unwrap();
+        assert_eq!(result.to_string(), "Inserted 1, replaced 0, updated 0 and skipped 0 records (assigned keys: 1)\n");
+
+        db.execute_command("INSERT text = \"b\" INTO notes").unwrap();
+        db.execute_command("DELETE 2 FROM notes").unwrap();
+
+        let result = db.execute_command("INSERT text = \"c\" INTO notes RETURNING id").unwrap();
+        assert_eq!(result.to_string(), "id: 3\n");
+
+        db.execute_command("INSERT id = 10, text = \"d\" INTO notes").unwrap();
+        let result = db.execute_command("INSERT text = \"e\" INTO notes").unwrap();
+        assert_eq!(result.to_string(), "Inserted 1, replaced 0, updated 0 and skipped 0 records (assigned keys: 11)\n");
+
+        let result = db.execute_command("INSERT text = 5 INTO notes");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("INSERT id = 9223372036854775807, text = \"last\" INTO notes");
+        assert!(matches!(result, Err(Error::ConstraintViolationError(_))));
+
+        let result = db.execute_command("CREATE tags KEY name AUTO
+        FIELDS name: String");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        db.execute_command("SAVE_AS parse_auto_test_output.txt").unwrap();
+
+        let mut restored = Database::<AnyKey>::new();
+        restored.execute_command("READ_FROM parse_auto_test_output.txt").unwrap();
+
+        let result = restored.execute_command("INSERT text = \"f\" INTO notes RETURNING id").unwrap();
+        assert_eq!(result.to_string(), "id: 12\n");
+
+        fs::remove_file("parse_auto_test_output.txt").unwrap();
+    }
+
+    #[test]
+    fn test_parse_constraints() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE users KEY id
+        FIELDS id: Int, email: String UNIQUE, age: Int, CHECK (age >= 0), CONSTRAINT adult CHECK (age >= 18 OR email = \"kid@example.com\")").unwrap();
+
+        db.execute_command("INSERT id = 1, email = \"a@example.com\", age = 30 INTO users").unwrap();
+
+        let result = db.execute_command("INSERT id = 2, email = \"a@example.com\", age = 40 INTO users");
+        assert!(matches!(&result, Err(Error::ConstraintViolationError(message)) if message.contains("UNIQUE (email)")));
+
+        let result = db.execute_command("INSERT id = 2, email = \"b@example.com\", age = -1 INTO users");
+        assert!(matches!(&result, Err(Error::ConstraintViolationError(message)) if message.contains("CHECK (age >= 0)")));
+
+        let result = db.execute_command("INSERT id = 2, email = \"b@example.com\", age = 12 INTO users");
+        assert!(matches!(&result, Err(Error::ConstraintViolationError(message)) if message.contains("adult")));
+
+        db.execute_command("INSERT id = 2, email = \"kid@example.com\", age = 12 INTO users").unwrap();
+        db.execute_command("INSERT OR REPLACE id = 1, email = \"a@example.com\", age = 31 INTO users").unwrap();
+
+        let result = db.execute_command("INSERT id = 1, email = \"x@example.com\", age = 1 INTO users ON CONFLICT DO UPDATE SET email = \"kid@example.com\"");
+        assert!(matches!(result, Err(Error::ConstraintViolationError(_))));
+
+        let result = db.execute_command("INSERT id = 1, email = \"x@example.com\", age = 1 INTO users ON CONFLICT DO UPDATE SET age = 10");
+        assert!(matches!(result, Err(Error::ConstraintViolationError(_))));
+
+        let result = db.execute_command("SELECT id, age FROM users ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 1, age: 31\nid: 2, age: 12\n");
+
+        let result = db.execute_command("EXPLAIN SELECT id FROM users WHERE email = \"kid@example.com\"").unwrap();
+        assert!(result.to_string().contains("full scan"), "{}", result);
+
+        db.execute_command("CREATE INDEX ON users (email)").unwrap();
+        let result = db.execute_command("EXPLAIN SELECT id FROM users WHERE email = \"kid@example.com\"").unwrap();
+        assert!(result.to_string().contains("index scan"), "{}", result);
+
+        let result = db.execute_command("INSERT id = 3, email = \"kid@example.com\", age = 40 INTO users");
+        assert!(matches!(result, Err(Error::ConstraintViolationError(_))));
+
+        db.execute_command("CREATE signups KEY id
+        FIELDS id: Int, email: String, age: Int").unwrap();
+        db.execute_command("INSERT id = 3, email = \"c@example.com\", age = 20 INTO signups").unwrap();
+        db.execute_command("INSERT id = 4, email = \"c@example.com\", age = 20 INTO signups").unwrap();
+
+        let result = db.execute_command("INSERT INTO users SELECT id, email, age FROM signups");
+        assert!(matches!(&result, Err(Error::ConstraintViolationError(message)) if message.contains("UNIQUE (email)")));
+        assert_eq!(db.database().tables().get("users").unwrap().len(), 2);
+
+        let result = db.execute_command("CREATE tagged KEY id
+        FIELDS id: Int, tags: List<String> UNIQUE");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("CREATE broken KEY id
+        FIELDS id: Int, CHECK (missing > 0)");
+        assert!(matches!(result, Err(Error::MissingFieldError(_))));
+
+        db.execute_command("CREATE contacts KEY id
+        FIELDS id: Int, email: String NOT NULL UNIQUE, phone: String NULL").unwrap();
+        let result = db.execute_command("INSERT id = 1, email = NULL INTO contacts");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+        db.execute_command("INSERT id = 1, email = \"c@example.com\" INTO contacts").unwrap();
+
+        let result = db.execute_command("CREATE broken KEY id
+        FIELDS id: Int, email: String NOT NULL NULL");
+        assert!(matches!(result, Err(Error::SyntaxError(Diagnostic { ref message, .. })) if message == "Field 'email' cannot be both NULL and NOT NULL"));
+    }
+
+    #[test]
+    fn test_parse_foreign_keys() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE authors KEY id
+        FIELDS id: Int, name: String").unwrap();
+        db.execute_command("CREATE books KEY id
+        FIELDS id: Int, author_id: Int REFERENCES authors ON DELETE CASCADE").unwrap();
+        db.execute_command("CREATE reviews KEY id
+        FIELDS id: Int, book_id: Int REFERENCES books").unwrap();
+
+        db.execute_command("INSERT id = 1, name = \"Le Guin\" INTO authors").unwrap();
+        db.execute_command("INSERT id = 2, name = \"Herbert\" INTO authors").unwrap();
+        db.execute_command("INSERT id = 10, author_id = 1 INTO books").unwrap();
+        db.execute_command("INSERT id = 11, author_id = 2 INTO books").unwrap();
+        db.execute_command("INSERT id = 100, book_id = 11 INTO reviews").unwrap();
+
+        let result = db.execute_command("INSERT id = 12, author_id = 3 INTO books");
+        assert!(matches!(&result, Err(Error::ConstraintViolationError(message)) if message.contains("REFERENCES authors")));
+
+        let result = db.execute_command("INSERT id = 10, author_id = 3 INTO books ON CONFLICT DO UPDATE SET author_id = 3");
+        assert!(matches!(result, Err(Error::ConstraintViolationError(_))));
+
+        let result = db.execute_command("DELETE 2 FROM authors");
+        assert!(matches!(&result, Err(Error::ConstraintViolationError(message)) if message.contains("'reviews' record '100'")));
+
+        let result = db.execute_command("DELETE 1 FROM authors").unwrap();
+        assert_eq!(result.to_string(), "Successfully deleted record 1 from table and 1 referencing records\n");
+
+        let result = db.execute_command("SELECT id FROM books ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 11\n");
+
+        db.execute_command("DELETE 100 FROM reviews").unwrap();
+        db.execute_command("DELETE 2 FROM authors").unwrap();
+        assert!(db.database().tables().get("books").unwrap().is_empty());
+
+        let result = db.execute_command("CREATE orphans KEY id
+        FIELDS id: Int, owner: Int REFERENCES missing");
+        assert!(matches!(result, Err(Error::NotExistError(_))));
+
+        let result = db.execute_command("CREATE mismatched KEY id
+        FIELDS id: Int, author: String REFERENCES authors");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("CREATE required KEY id
+        FIELDS id: Int, author_id: Int REFERENCES authors ON DELETE SET NULL");
+        assert!(matches!(&result, Err(Error::TypeError(message)) if message.contains("must be declared NULL")));
+
+        db.execute_command("INSERT id = 3, name = \"Butler\" INTO authors").unwrap();
+        db.execute_command("CREATE essays KEY id
+        FIELDS id: Int, author_id: Int NULL REFERENCES authors ON DELETE SET NULL").unwrap();
+        db.execute_command("INSERT id = 1, author_id = 3 INTO essays").unwrap();
+        db.execute_command("INSERT id = 2 INTO essays").unwrap();
+
+        let result = db.execute_command("DELETE 3 FROM authors").unwrap();
+        assert_eq!(result.to_string(), "Successfully deleted record 3 from table\n");
+
+        let result = db.execute_command("SELECT id, author_id FROM essays WHERE author_id IS NULL ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 1, author_id: NULL\nid: 2, author_id: NULL\n");
+
+        db.execute_command("CREATE employees KEY id
+        FIELDS id: Int, manager: Int REFERENCES employees").unwrap();
+        db.execute_command("INSERT id = 1, manager = 1 INTO employees").unwrap();
+        db.execute_command("DELETE 1 FROM employees").unwrap();
+    }
+
+    #[test]
+    fn test_parse_nullable_fields() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE books KEY id
+        FIELDS id: Int, title: String, subtitle: String NULL").unwrap();
+        db.execute_command("INSERT id = 1, title = \"Dune\" INTO books").unwrap();
+        db.execute_command("INSERT id = 2, title = \"Kindred\", subtitle = \"A Novel\" INTO books").unwrap();
+        db.execute_command("INSERT id = 3, title = \"Ubik\", subtitle = NULL INTO books").unwrap();
+
+        let result = db.execute_command("INSERT id = 4, title = NULL INTO books");
+        assert!(matches!(&result, Err(Error::TypeError(message)) if message.contains("'title' cannot be NULL")));
+
+        let result = db.execute_command("SELECT id FROM books WHERE subtitle IS NOT NULL").unwrap();
+        assert_eq!(result.to_string(), "id: 2\n");
+
+        let result = db.execute_command("SELECT id FROM books WHERE subtitle = \"A Novel\" OR subtitle IS NULL ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 1\nid: 2\nid: 3\n");
+
+        let result = db.execute_command("SELECT id FROM books WHERE subtitle != \"A Novel\"").unwrap();
+        assert_eq!(result.affected_rows(), 0);
+
+        let result = db.execute_command("SELECT id FROM books WHERE subtitle = NULL");
+        assert!(matches!(&result, Err(Error::SyntaxError(Diagnostic { message, .. })) if message.contains("use IS NULL")));
+
+        let select = db.prepare("SELECT id FROM books WHERE subtitle = ?").unwrap();
+        let result = db.execute_prepared(&select, &[IntermediateValue::Null]);
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        let result = db.execute_command("SELECT id FROM books ORDER_BY subtitle, id").unwrap();
+        assert_eq!(result.to_string(), "id: 2\nid: 1\nid: 3\n");
+
+        let result = db.execute_command("CREATE MATERIALIZED VIEW subtitled AS SELECT id, subtitle FROM books").unwrap();
+        assert_eq!(result.to_string(), "Materialized view 'subtitled' created with 3 records\n");
+
+        let result = db.execute_command("DESCRIBE subtitled").unwrap();
+        assert!(result.to_string().contains("field: \"subtitle\", type: \"String\", key: false, nullable: true"));
+
+        db.execute_command("CREATE reviews KEY id
+        FIELDS id: Int, stars: Int NULL, CHECK (stars >= 1)").unwrap();
+        let result = db.execute_command("INSERT id = 1 INTO reviews");
+        assert!(matches!(result, Err(Error::ConstraintViolationError(_))));
+        db.execute_command("CREATE ratings KEY id
+        FIELDS id: Int, stars: Int NULL, CHECK (stars IS NULL OR stars >= 1)").unwrap();
+        db.execute_command("INSERT id = 1 INTO ratings").unwrap();
+
+        let result = db.execute_command("DESCRIBE books").unwrap();
+        assert!(result.to_string().contains("field: \"subtitle\", type: \"String\", key: false, nullable: true"));
+
+        let result = db.execute_command("CREATE broken KEY id
+        FIELDS id: Int NULL, title: String");
+        assert!(matches!(result, Err(Error::TypeError(_))));
+
+        db.execute_command("SAVE_AS parse_nullable_test_output.txt").unwrap();
+
+        let mut restored = AnyDatabase::new();
+        restored.execute_command("READ_FROM parse_nullable_test_output.txt").unwrap();
+
+        let result = restored.execute_command("SELECT id, subtitle FROM books ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 1, subtitle: NULL\nid: 2, subtitle: \"A Novel\"\nid: 3, subtitle: NULL\n");
+
+        fs::remove_file("parse_nullable_test_output.txt").unwrap();
+    }
+
+    #[test]
+    fn test_parse_create_index() {
+        let mut db = AnyDatabase::new();
+
+        db.execute_command("CREATE books KEY id
+        FIELDS id: Int, year: Int, title: String").unwrap();
+
+        for (id, year) in [(1, 1970), (2, 1985), (3, 1999), (4, 2010)] {
+            db.execute_command(&format!("INSERT id = {}, year = {}, title = \"Book {}\" INTO books", id, year, id)).unwrap();
+        }
+
+        let result = db.execute_command("CREATE INDEX ON books (year)").unwrap();
+        assert_eq!(result.to_string(), "Index on 'books' (year) created successfully\n");
+
+        let result = db.execute_command("EXPLAIN SELECT id FROM books WHERE year >= 1985 AND title != \"Book 3\"").unwrap();
+        assert!(result.to_string().contains("index scan of 'books'"));
+
+        let result = db.execute_command("EXPLAIN SELECT id FROM books WHERE year >= 1985 OR title = \"Book 1\"").unwrap();
+        assert!(result.to_string().contains("full scan of 'books'"));
+
+        db.execute_command("INSERT id = 5, year = 1985, title = \"Book 5\" INTO books").unwrap();
+        db.execute_command("INSERT OR REPLACE id = 1, year = 1985, title = \"Book 1\" INTO books").unwrap();
+        db.execute_command("INSERT id = 4, year = 0, title = \"\" INTO books ON CONFLICT DO UPDATE SET year = 2020").unwrap();
+        db.execute_command("DELETE 2 FROM books").unwrap();
+
+        let result = db.execute_command("SELECT id FROM books WHERE year = 1985 ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 1\nid: 5\n");
+
+        let result = db.execute_command("SELECT id FROM books WHERE year > 1985 AND year <= 2020 ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 3\nid: 4\n");
+
+        let result = db.execute_command("SELECT id FROM books WHERE year < 1980 OR year = 2020 ORDER_BY id").unwrap();
+        assert_eq!(result.to_string(), "id: 4\n");
+
+        let result = db.execute_command("CREATE INDEX ON books (missing)");
+        assert!(matches!(result, Err(Error::MissingFieldError(_))));
     }
 
     #[test]
     fn test_parse_save() {
-        let mut db = AnyDatabase::IntDatabase(Database::<i64>::new());
+        let mut db = AnyDatabase::new();
 
         db.execute_command("CREATE books KEY id
              FIELDS id: Int, year: Int"
@@ -656,7 +2299,7 @@ mod tests {
 
         fs::write("parse_read_test_input.txt", script).unwrap();
 
-        let mut db = Database::<String>::new();
+        let mut db = Database::<AnyKey>::new();
         let result = db.execute_command("READ_FROM parse_read_test_input.txt");
 
         assert!(matches!(result, Ok(ExecutionSuccessValue::SuccessFileOperation(_))));